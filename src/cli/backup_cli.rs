@@ -0,0 +1,257 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::backup::{self, BackupFormat};
+use crate::minecraft::server::Server;
+use crate::utils::{format_size, parse_size};
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct BackupArgs {
+    #[command(subcommand)]
+    pub action: Option<BackupAction>,
+    /// Server to back up
+    pub name: Option<String>,
+    /// Archive format (defaults to the server's backup policy)
+    #[clap(short, long, value_enum)]
+    pub format: Option<BackupFormat>,
+    /// Only archive the world folders
+    #[clap(short, long)]
+    pub worlds_only: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BackupAction {
+    /// List the backups of a server, newest first
+    List { name: String },
+    /// Delete the backups the server's retention policy doesn't keep
+    Prune {
+        name: String,
+        /// Print what would be deleted without deleting anything
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Read backups back and compare every file with its manifest
+    Check {
+        name: String,
+        /// Only check this backup
+        id: Option<String>,
+    },
+    /// Write the files of a backup to a folder
+    Extract {
+        name: String,
+        id: String,
+        directory: PathBuf,
+    },
+    /// Show or change where and how a server is backed up
+    Policy {
+        name: String,
+        /// Folder to store the archives in
+        #[clap(short, long)]
+        directory: Option<PathBuf>,
+        /// Default archive format
+        #[clap(short, long, value_enum)]
+        format: Option<BackupFormat>,
+        /// Only archive the world folders by default
+        #[clap(short, long)]
+        worlds_only: Option<bool>,
+        /// Keep the N most recent backups (0 to unset)
+        #[clap(long)]
+        keep_last: Option<usize>,
+        /// Keep the newest backup of each of the last N days (0 to unset)
+        #[clap(long)]
+        keep_daily: Option<usize>,
+        /// Keep the newest backup of each of the last N weeks (0 to unset)
+        #[clap(long)]
+        keep_weekly: Option<usize>,
+        /// Keep the backups under this total size, e.g. 20G ("none" to unset). Incremental
+        /// backups count at their full size, not only their new chunks
+        #[clap(long)]
+        max_size: Option<String>,
+    },
+}
+
+fn get_server(config: &Config, name: &str) -> Result<Server, Error> {
+    config
+        .get_server(name)
+        .ok_or(Error::ResourceNotFound("Server not found".to_string()))
+}
+
+// 0 unsets a retention count
+fn count(value: usize) -> Option<usize> {
+    (value > 0).then_some(value)
+}
+
+pub fn manage_backup_action(args: BackupArgs, config: &Config) -> Result<(), Error> {
+    match args.action {
+        None => {
+            let name = args.name.ok_or(Error::ResourceNotFound(
+                "Server name is required".to_string(),
+            ))?;
+            let server = get_server(config, &name)?;
+            let format = args.format.unwrap_or(server.backup.format);
+            let worlds_only = args.worlds_only || server.backup.worlds_only;
+            let manifest = backup::create_consistent(&server, format, worlds_only)?;
+            println!(
+                "📝 Created backup {} ({})",
+                manifest.id,
+                format_size(manifest.size)
+            );
+            prune(&server, false)?;
+        }
+        Some(BackupAction::List { name }) => {
+            let server = get_server(config, &name)?;
+            let backups = backup::list(&server)?;
+            println!(
+                "📝 Backups of {} in {}:",
+                server.server_name,
+                backup::directory(&server)?.display()
+            );
+            for backup in &backups {
+                backup.print();
+            }
+            let total: u64 = backups.iter().map(|b| b.stored.unwrap_or(b.size)).sum();
+            println!(
+                "📝 {} backup(s), {} stored in total.",
+                backups.len(),
+                format_size(total)
+            );
+        }
+        Some(BackupAction::Prune { name, dry_run }) => {
+            let server = get_server(config, &name)?;
+            if !server.backup.has_retention() {
+                println!("🚨 {name} has no retention policy, set one with `backup policy`!");
+                return Ok(());
+            }
+            prune(&server, dry_run)?;
+        }
+        Some(BackupAction::Check { name, id }) => {
+            let server = get_server(config, &name)?;
+            let backups = match id {
+                Some(id) => vec![backup::find(&server, &id)?],
+                None => backup::list(&server)?,
+            };
+            let mut damaged = 0;
+            for backup in &backups {
+                let problems = backup::check(&server, backup)?;
+                if problems.is_empty() {
+                    println!("  ✅ {} is intact", backup.id);
+                    continue;
+                }
+                damaged += 1;
+                println!("  🚨 {} is damaged:", backup.id);
+                for problem in &problems {
+                    println!("      ➥ {problem}");
+                }
+            }
+            println!("📝 Checked {} backup(s), {damaged} damaged.", backups.len());
+        }
+        Some(BackupAction::Extract {
+            name,
+            id,
+            directory,
+        }) => {
+            let server = get_server(config, &name)?;
+            let backup = backup::find(&server, &id)?;
+            println!("📝 Extracting {id} to {}...", directory.display());
+            backup::extract(&server, &backup, &directory)?;
+            println!("📝 Extracted {} file(s)!", backup.files.len());
+        }
+        Some(BackupAction::Policy {
+            name,
+            directory,
+            format,
+            worlds_only,
+            keep_last,
+            keep_daily,
+            keep_weekly,
+            max_size,
+        }) => {
+            let mut server = get_server(config, &name)?;
+            let settings = &mut server.backup;
+            if let Some(directory) = directory {
+                settings.directory = Some(directory);
+            }
+            if let Some(format) = format {
+                settings.format = format;
+            }
+            if let Some(worlds_only) = worlds_only {
+                settings.worlds_only = worlds_only;
+            }
+            if let Some(keep_last) = keep_last {
+                settings.keep_last = count(keep_last);
+            }
+            if let Some(keep_daily) = keep_daily {
+                settings.keep_daily = count(keep_daily);
+            }
+            if let Some(keep_weekly) = keep_weekly {
+                settings.keep_weekly = count(keep_weekly);
+            }
+            if let Some(max_size) = max_size {
+                if max_size.eq_ignore_ascii_case("none") {
+                    settings.max_total_size = None;
+                } else {
+                    parse_size(&max_size)?;
+                    settings.max_total_size = Some(max_size);
+                }
+            }
+            server.write();
+            println!(
+                "📝 Backup policy for {name} ({}):",
+                backup::directory(&server)?.display()
+            );
+            server.backup.print();
+        }
+    }
+    Ok(())
+}
+
+fn prune(server: &Server, dry_run: bool) -> Result<(), Error> {
+    let (pruned, freed) = backup::prune(server, dry_run)?;
+    if pruned.is_empty() {
+        return Ok(());
+    }
+    for backup in &pruned {
+        backup.print();
+    }
+    if dry_run {
+        println!(
+            "📝 Dry run: {} backup(s) ({}) would be deleted.",
+            pruned.len(),
+            format_size(freed)
+        );
+    } else {
+        println!(
+            "📝 Pruned {} backup(s), freeing {}.",
+            pruned.len(),
+            format_size(freed)
+        );
+    }
+    Ok(())
+}
+
+pub fn restore_backup(
+    config: &Config,
+    name: &str,
+    id: &str,
+    worlds_only: bool,
+) -> Result<(), Error> {
+    let server = get_server(config, name)?;
+    let backup = backup::find(&server, id)?;
+    println!(
+        "📝 Restoring {}{} from {id}...",
+        server.server_name,
+        if worlds_only || backup.worlds_only {
+            "'s worlds"
+        } else {
+            ""
+        }
+    );
+    let (safety, restored) = backup::restore(&server, &backup, worlds_only)?;
+    println!(
+        "📝 Restored {} ({} {})! The previous state was saved as {}",
+        restored.server_name, restored.jar_name, restored.version, safety.id
+    );
+    Ok(())
+}
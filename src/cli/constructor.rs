@@ -1,5 +1,14 @@
+use crate::cli::backup_cli::BackupArgs;
 use crate::cli::config_cli::ConfigAction;
+use crate::cli::java_cli::JavaAction;
+use crate::cli::jvm_cli::JvmArgs;
+use crate::cli::network_cli::NetworkAction;
+use crate::cli::plugin_cli::PluginAction;
+use crate::cli::template_cli::TemplateAction;
+use crate::cli::trash_cli::TrashAction;
+use crate::minecraft::player_lists::{PlayerList, SyncMode};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
@@ -20,9 +29,30 @@ pub enum DJ {
         location: String,
         version: String,
         build: Option<String>,
+        /// Create the location when it doesn't exist
+        #[clap(long)]
+        create_dir: bool,
+        /// Accept the Minecraft EULA for this server (written before every start)
+        #[clap(long)]
+        accept_eula: bool,
+        /// Lay down a template before the first boot
+        #[clap(long)]
+        template: Option<String>,
     },
     /// Start an existing server by providing its name
-    Start { name: String },
+    Start {
+        name: String,
+        /// Accept the Minecraft EULA without asking
+        #[clap(long)]
+        accept_eula: bool,
+    },
+    /// Converge a server folder to a manifest file (jar, memory, properties, plugins...)
+    Apply {
+        manifest: PathBuf,
+        /// Only print what would change
+        #[clap(long)]
+        plan: bool,
+    },
     /// Commands related to server configuration settings
     Config {
         #[command(subcommand)]
@@ -38,6 +68,26 @@ pub enum DJ {
         #[command(subcommand)]
         action: ThemeAction,
     },
+    /// Install, list or remove Java runtimes
+    Java {
+        #[command(subcommand)]
+        action: JavaAction,
+    },
+    /// Run a Velocity proxy in front of backend servers
+    Network {
+        #[command(subcommand)]
+        action: NetworkAction,
+    },
+    /// List, create or delete server templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+    /// List, restore or permanently delete deleted servers
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -45,23 +95,179 @@ pub enum ServerAction {
     /// List all servers
     List,
     /// Generate a server with a wizard
-    Generate,
+    Generate(GenerateArgs),
     /// Get info about a server with the given name
     Info { name: String },
     /// Start a server with the given name
-    Start { name: String },
-    /// Delete a server with the given name
-    Delete { name: String },
+    Start {
+        name: String,
+        /// Accept the Minecraft EULA without asking
+        #[clap(long)]
+        accept_eula: bool,
+    },
+    /// Move a server with the given name to the trash
+    Delete {
+        name: String,
+        /// Skip typing the server name to confirm
+        #[clap(short, long)]
+        yes: bool,
+    },
     /// Add a server with the given location
     Add { location: String },
     /// Get plugins for a server with the given name
-    Plugins { name: String },
+    Plugins {
+        name: String,
+        #[command(subcommand)]
+        action: Option<PluginAction>,
+    },
     /// Assign an IP to a server with the given name
     AssignIP { name: String, ip: String },
     /// Optimize a server with the given name
     Optimize { name: String },
     /// Import existing servers from a directory
     Import { location: String },
+    /// Add or remove tags on a server with the given name
+    Tag {
+        name: String,
+        tags: Vec<String>,
+        /// Remove the given tags instead of adding them
+        #[clap(short, long)]
+        remove: bool,
+    },
+    /// Sync whitelist, ops and ban lists from a source server to other servers
+    Sync(SyncArgs),
+    /// Show or set the heap sizes of a server, checked against the host's memory
+    Memory {
+        name: String,
+        /// Minimum heap (-Xms), e.g. 2G
+        #[clap(long)]
+        min: Option<String>,
+        /// Maximum heap (-Xmx), e.g. 6G
+        #[clap(long)]
+        max: Option<String>,
+        /// Count the server as started automatically when checking memory. Only used for
+        /// that check: boxes doesn't start servers on its own
+        #[clap(long)]
+        auto_start: Option<bool>,
+    },
+    /// Show or change the JVM flags and arguments of a server
+    Jvm(JvmArgs),
+    /// Back up a server, or list and prune its backups
+    Backup(BackupArgs),
+    /// Copy a server into a new instance with its own name, folder and ports
+    Clone {
+        source: String,
+        new_name: String,
+        location: String,
+        /// Leave out the world folders
+        #[clap(long)]
+        without_worlds: bool,
+        /// Leave out the plugins folder and plugins.lock
+        #[clap(long)]
+        without_plugins: bool,
+    },
+    /// Rename a stopped server
+    Rename { name: String, new_name: String },
+    /// Move a stopped server's folder to a new location
+    Move { name: String, location: String },
+    /// List servers running an older build than the latest one of their version
+    Outdated {
+        /// Print the result as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Change a server's Minecraft version or build (a backup is taken first)
+    Upgrade {
+        name: String,
+        /// Version to move to (defaults to the current one)
+        #[clap(short, long)]
+        version: Option<String>,
+        /// Build to use, or "latest"
+        #[clap(short, long)]
+        build: Option<String>,
+        /// Don't ask before moving to an older version
+        #[clap(long)]
+        allow_downgrade: bool,
+    },
+    /// Switch a server to another software, keeping its worlds and config
+    Migrate {
+        name: String,
+        /// Jar to switch to
+        #[clap(long)]
+        to: String,
+        /// Version to run (defaults to the current one)
+        #[clap(short, long)]
+        version: Option<String>,
+        /// Don't ask before orphaning plugins
+        #[clap(long)]
+        yes: bool,
+    },
+    /// Restore a server from one of its backups (a safety backup is taken first)
+    Restore {
+        name: String,
+        backup_id: String,
+        /// Only restore the world folders
+        #[clap(short, long)]
+        worlds_only: bool,
+    },
+}
+
+// Every step of the generate wizard, which only asks for what isn't given
+#[derive(clap::Args, Debug, Default)]
+pub struct GenerateArgs {
+    /// Server name
+    #[clap(long)]
+    pub name: Option<String>,
+    /// Jar to use (Paper, Purpur...)
+    #[clap(long)]
+    pub jar: Option<String>,
+    /// Minecraft version, or "latest"
+    #[clap(long)]
+    pub version: Option<String>,
+    /// Jar build, or "latest" (the default without prompts)
+    #[clap(long)]
+    pub build: Option<String>,
+    /// Folder to create the server in
+    #[clap(long)]
+    pub location: Option<PathBuf>,
+    /// Create the location when it doesn't exist
+    #[clap(long)]
+    pub create_dir: bool,
+    /// Minimum heap (-Xms), e.g. 2G
+    #[clap(long)]
+    pub min_memory: Option<String>,
+    /// Maximum heap (-Xmx), e.g. 6G
+    #[clap(long)]
+    pub max_memory: Option<String>,
+    /// Port in server.properties
+    #[clap(long)]
+    pub port: Option<u16>,
+    /// Accept the Minecraft EULA for this server (written before every start)
+    #[clap(long)]
+    pub accept_eula: bool,
+    /// Lay down a template before the first boot (its settings are defaults for the prompts)
+    #[clap(long)]
+    pub template: Option<String>,
+    /// Fail instead of prompting for anything missing
+    #[clap(long)]
+    pub non_interactive: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SyncArgs {
+    pub source: String,
+    pub targets: Vec<String>,
+    /// Sync to every server with the given tag
+    #[clap(short, long)]
+    pub tag: Option<String>,
+    #[clap(short, long, value_enum, default_value_t = SyncMode::Merge)]
+    pub mode: SyncMode,
+    /// Only sync the given lists (defaults to all of them)
+    #[clap(short, long, value_enum, value_delimiter = ',')]
+    pub lists: Vec<PlayerList>,
+    /// Print the report without changing any files
+    #[clap(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -3,22 +3,38 @@
     clippy::cognitive_complexity,
     clippy::debug_assert_with_mut_call,
     clippy::doc_markdown,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::pedantic,
     clippy::complexity
 )]
 
+use crate::cli::backup_cli;
 use crate::cli::config_cli;
-use crate::cli::constructor::{Args, ServerAction, DJ};
+use crate::cli::java_cli;
+use crate::cli::jvm_cli;
+use crate::cli::network_cli;
+use crate::cli::plugin_cli;
+use crate::cli::template_cli;
+use crate::cli::trash_cli;
+use crate::cli::constructor::{Args, ServerAction, SyncArgs, DJ};
 use crate::cli::generator;
 use crate::config::Config;
 use crate::error::Error;
+use crate::minecraft::instances::{self, CloneOptions};
 use crate::minecraft::jars::load;
+use crate::minecraft::manifest::{self, Manifest};
+use crate::minecraft::memory::{self, DEFAULT_HEAP};
+use crate::minecraft::migration;
+use crate::minecraft::player_lists::{self, PlayerList};
+use crate::minecraft::plugin_sources::PluginApis;
 use crate::minecraft::server::Server;
 use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::server_updates;
+use crate::minecraft::templates::Templates;
+use crate::minecraft::trash::Trash;
 use crate::themes::theme::Theme;
-use crate::utils::read_line;
+use crate::utils::{format_size, read_line};
 use std::path::Path;
 
 use super::constructor::ThemeAction;
@@ -31,7 +47,10 @@ pub(crate) fn execute(args: Args, mut config: Config, theme: &Theme) -> Result<(
             jar,
             version,
             build,
-            mut location,
+            location,
+            create_dir,
+            accept_eula,
+            template,
         } => {
             // Print with emoji
             println!("🔥 Creating server...");
@@ -39,28 +58,32 @@ pub(crate) fn execute(args: Args, mut config: Config, theme: &Theme) -> Result<(
             let jar = jars
                 .get_jar(&jar)
                 .ok_or(Error::ResourceNotFound("Jar not found".to_string()))?;
-            let build = match build {
-                Some(build) => build,
-                None => jar
-                    .get_latest_build(version.as_str())
-                    .ok_or(Error::ResourceNotFound("Jar build not found".to_string()))?
-                    .to_string(),
+            let version = if version.eq_ignore_ascii_case("latest") {
+                generator::resolve_version(jar, &version)?
+            } else {
+                version
             };
-            let mut path = Path::new(&location);
-            while !path.exists() {
-                println!("🚨 Path does not exist!");
-                location = read_line("🎚️ Please enter the server location:")?;
-                path = Path::new(&location);
+            let build = generator::resolve_build(jar, &version, build.as_deref())?;
+            let templates = Templates::open()?;
+            let template = template.map(|name| templates.get(&name)).transpose()?;
+            let path = generator::create_location(&location, create_dir)?;
+            let mut server = jar.download(&version, &build, &name, &path)?;
+            if let Some(template) = &template {
+                println!("🔥 Laying down template {}...", template.name);
+                server = templates.apply(template, &server)?;
+            }
+            if accept_eula {
+                server.remember_eula()?;
             }
-            let server = jar.download(&version, &build, &name, path)?;
             config.add_server(&server, true);
         }
-        DJ::Start { name } => {
+        DJ::Start { name, accept_eula } => {
             let mut server = config
                 .get_server(&name)
                 .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
-            server.run(false)?;
+            server.run(accept_eula)?;
         }
+        DJ::Apply { manifest, plan } => apply_manifest(&manifest, plan, &mut config)?,
         DJ::Config { action } => {
             config_cli::manage_config_action(action, &config)?;
         }
@@ -68,10 +91,15 @@ pub(crate) fn execute(args: Args, mut config: Config, theme: &Theme) -> Result<(
         // Actions
         DJ::Server { action } => handle_server_action(action, &mut config, verbose)?,
         DJ::Theme { action } => handle_theme_action(action, verbose),
+        DJ::Java { action } => java_cli::manage_java_action(action)?,
+        DJ::Network { action } => network_cli::manage_network_action(action, &mut config)?,
+        DJ::Template { action } => template_cli::manage_template_action(action, &config)?,
+        DJ::Trash { action } => trash_cli::manage_trash_action(action, &mut config)?,
     }
     Ok(())
 }
 
+#[allow(clippy::too_many_lines)]
 fn handle_server_action(
     action: ServerAction,
     config: &mut Config,
@@ -81,8 +109,8 @@ fn handle_server_action(
         ServerAction::List => {
             config.print_info()?;
         }
-        ServerAction::Generate => {
-            generator::prompt_wizard(config)?;
+        ServerAction::Generate(args) => {
+            generator::prompt_wizard(config, args)?;
         }
         ServerAction::Info { name } => {
             let server = config
@@ -90,36 +118,50 @@ fn handle_server_action(
                 .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
             server.print_info();
         }
-        ServerAction::Start { name } => {
+        ServerAction::Start { name, accept_eula } => {
             let mut server = config
                 .get_server(&name)
                 .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
-            server.run(false)?;
+            server.run(accept_eula)?;
         }
-        ServerAction::Delete { name } => {
+        ServerAction::Delete { name, yes } => {
             let server = config
                 .get_server(&name)
                 .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
-            server.delete();
+            if server.is_running() {
+                return Err(Error::ServerRunning(server.server_name));
+            }
+            if !yes {
+                let typed = read_line(&format!(
+                    "🚨 Type {} to confirm deleting {}:",
+                    server.server_name,
+                    server.location.display()
+                ))?;
+                if typed.trim() != server.server_name {
+                    println!("🚨 Name does not match, nothing was deleted.");
+                    return Ok(());
+                }
+            }
+            let entry = server.delete()?;
+            if let Err(e) = config.remove_server(&server.server_name) {
+                // Puts the folder back where the config still expects it
+                Trash::open()?.restore(&entry)?;
+                return Err(e);
+            }
+            println!(
+                "📝 Deleted {}! Undo with `boxes trash restore {}`",
+                server.server_name, entry.id
+            );
         }
         ServerAction::Add { location } => {
             let server = Server::from_path(&location)?;
             config.add_server(&server, true);
         }
-        ServerAction::Plugins { name } => {
+        ServerAction::Plugins { name, action } => {
             let server = config
                 .get_server(&name)
                 .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
-            let plugins = server.plugins();
-            println!(
-                "📝 Getting plugins for {} ({} plugin(s))...",
-                name,
-                plugins.len()
-            );
-            for plugin in plugins {
-                let plugin = plugin.to_string_lossy().to_string();
-                println!(" - {plugin}");
-            }
+            plugin_cli::manage_plugin_action(&server, action, config)?;
         }
         ServerAction::AssignIP { name, ip } => {
             let server = config
@@ -168,9 +210,443 @@ fn handle_server_action(
                 }
                 config.add_server(&server, true);
             }
-            confy::store("boxes", None, config).expect("🚨 Config file could not be saved!");
+            config.save().expect("🚨 Config file could not be saved!");
             println!("📝 Imported servers from {location}!");
         }
+        ServerAction::Tag { name, tags, remove } => {
+            let mut server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            for tag in tags {
+                let tag = tag.to_lowercase();
+                if remove {
+                    server.tags.retain(|t| t != &tag);
+                } else if !server.tags.contains(&tag) {
+                    server.tags.push(tag);
+                }
+            }
+            server.write();
+            println!("📝 Tags for {name}: {}", server.tags.join(", "));
+        }
+        ServerAction::Sync(args) => sync_player_lists(args, config)?,
+        ServerAction::Memory {
+            name,
+            min,
+            max,
+            auto_start,
+        } => {
+            let server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            set_memory(config, server, min, max, auto_start)?;
+        }
+        ServerAction::Jvm(args) => jvm_cli::manage_jvm_action(args, config)?,
+        ServerAction::Backup(args) => backup_cli::manage_backup_action(args, config)?,
+        ServerAction::Clone {
+            source,
+            new_name,
+            location,
+            without_worlds,
+            without_plugins,
+        } => {
+            let options = CloneOptions {
+                without_worlds,
+                without_plugins,
+            };
+            clone_server(config, &source, &new_name, Path::new(&location), &options)?;
+        }
+        ServerAction::Rename { name, new_name } => {
+            let server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            if let Some(existing) = config.get_server(&new_name) {
+                if existing.location != server.location {
+                    return Err(Error::ResourceNotFound(format!(
+                        "A server named {new_name} already exists"
+                    )));
+                }
+            }
+            println!("📝 Renaming {} to {new_name}...", server.server_name);
+            let renamed = instances::rename(&server, &new_name)?;
+            if let Err(e) = config.update_server(&server.server_name, &renamed) {
+                // Puts the backups and server_box.toml back under the old name
+                instances::rename(&renamed, &server.server_name)?;
+                return Err(e);
+            }
+            println!("📝 Renamed {} to {new_name}!", server.server_name);
+        }
+        ServerAction::Move { name, location } => {
+            let server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            println!(
+                "📝 Moving {} from {} to {location}...",
+                server.server_name,
+                server.location.display()
+            );
+            let moved = instances::relocate(&server, Path::new(&location))?;
+            if let Err(e) = config.update_server(&server.server_name, &moved) {
+                instances::relocate(&moved, &server.location)?;
+                return Err(e);
+            }
+            println!(
+                "📝 Moved {} to {}!",
+                server.server_name,
+                moved.location.display()
+            );
+        }
+        ServerAction::Outdated { json } => {
+            outdated_servers(config, json)?;
+        }
+        ServerAction::Upgrade {
+            name,
+            version,
+            build,
+            allow_downgrade,
+        } => {
+            let server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            upgrade_server(&server, version.as_deref(), build.as_deref(), allow_downgrade)?;
+        }
+        ServerAction::Migrate {
+            name,
+            to,
+            version,
+            yes,
+        } => {
+            let server = config
+                .get_server(&name)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            migrate_server(&server, &to, version.as_deref(), yes)?;
+        }
+        ServerAction::Restore {
+            name,
+            backup_id,
+            worlds_only,
+        } => backup_cli::restore_backup(config, &name, &backup_id, worlds_only)?,
+    }
+    Ok(())
+}
+
+fn clone_server(
+    config: &mut Config,
+    source: &str,
+    name: &str,
+    location: &Path,
+    options: &CloneOptions,
+) -> Result<(), Error> {
+    let source = config
+        .get_server(source)
+        .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+    if config.get_server(name).is_some() {
+        return Err(Error::ResourceNotFound(format!(
+            "A server named {name} already exists"
+        )));
+    }
+    let taken: Vec<u16> = config
+        .servers
+        .iter()
+        .filter_map(|info| config.get_server(&info.server_name))
+        .flat_map(|server| instances::ports(&server))
+        .collect();
+    println!(
+        "📝 Cloning {} to {}...",
+        source.server_name,
+        location.display()
+    );
+    let (clone, report) = instances::clone_server(&source, name, location, options, &taken)?;
+    println!(
+        "📝 Copied {} file(s), {} reflinked and {} hard linked",
+        report.copied + report.reflinked + report.hardlinked,
+        report.reflinked,
+        report.hardlinked
+    );
+    config.add_server(&clone, true);
+    println!(
+        "📝 Cloned {} as {} (port {})!",
+        source.server_name,
+        clone.server_name,
+        instances::ports(&clone)
+            .first()
+            .map_or_else(|| "unknown".to_string(), u16::to_string)
+    );
+    Ok(())
+}
+
+fn set_memory(
+    config: &Config,
+    mut server: Server,
+    min: Option<String>,
+    max: Option<String>,
+    auto_start: Option<bool>,
+) -> Result<(), Error> {
+    if min.is_some() || max.is_some() {
+        let current = |size: &Option<String>| size.clone().unwrap_or(DEFAULT_HEAP.to_string());
+        let (min, max) = memory::validate(
+            &min.unwrap_or_else(|| current(&server.xms)),
+            &max.unwrap_or_else(|| current(&server.xmx)),
+        )?;
+        server.xms = Some(min);
+        server.xmx = Some(max);
+    }
+    if let Some(auto_start) = auto_start {
+        server.auto_start = auto_start;
+    }
+    server.write();
+    println!(
+        "📝 {}: Xms {}, Xmx {}{}",
+        server.server_name,
+        server.xms.as_deref().unwrap_or(DEFAULT_HEAP),
+        server.xmx.as_deref().unwrap_or(DEFAULT_HEAP),
+        if server.auto_start {
+            ", started automatically"
+        } else {
+            ""
+        }
+    );
+    let Some(host) = memory::host_memory() else {
+        println!("🚨 Couldn't read the memory of this machine!");
+        return Ok(());
+    };
+    let mut servers: Vec<Server> = config
+        .servers
+        .iter()
+        .filter(|info| info.server_name != server.server_name)
+        .filter_map(|info| config.get_server(&info.server_name))
+        .collect();
+    servers.push(server.clone());
+    let total = memory::auto_start_total(&servers);
+    if total > host.total {
+        println!(
+            "⚠️ Auto-started servers can use up to {} of heap, more than the {} of RAM this machine has!",
+            format_size(total),
+            format_size(host.total)
+        );
+    }
+    let suggestion = memory::suggest(host.available);
+    println!(
+        "💡 {} of {} available, suggested: --min {suggestion} --max {suggestion}",
+        format_size(host.available),
+        format_size(host.total)
+    );
+    Ok(())
+}
+
+fn outdated_servers(config: &Config, json: bool) -> Result<(), Error> {
+    let jars = load()?;
+    let mut statuses = Vec::new();
+    for info in &config.servers {
+        let Some(server) = config.get_server(&info.server_name) else {
+            continue;
+        };
+        let Some(jar) = jars.get_jar(&server.jar_name) else {
+            eprintln!(
+                "⚠️ Skipping {}: unknown jar {}",
+                server.server_name, server.jar_name
+            );
+            continue;
+        };
+        if !json {
+            println!("📝 Checking {} for new builds...", server.server_name);
+        }
+        match server_updates::check(&server, jar) {
+            Ok(status) => statuses.push(status),
+            Err(e) => eprintln!("⚠️ Couldn't check {}: {e}", server.server_name),
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        return Ok(());
+    }
+    if statuses.is_empty() {
+        println!("🚨 No servers to check!");
+        return Ok(());
+    }
+    server_updates::print_table(&statuses);
+    for status in statuses.iter().filter(|s| s.is_outdated()) {
+        server_updates::print_changes(status);
+    }
+    let outdated = statuses.iter().filter(|s| s.is_outdated()).count();
+    println!("📝 {outdated} server(s) can be upgraded.");
+    Ok(())
+}
+
+fn upgrade_server(
+    server: &Server,
+    version: Option<&str>,
+    build: Option<&str>,
+    allow_downgrade: bool,
+) -> Result<(), Error> {
+    let jars = load()?;
+    let jar = jars
+        .get_jar(&server.jar_name)
+        .ok_or(Error::ResourceNotFound("Jar not found".to_string()))?;
+    let (version, build) = server_updates::resolve(jar, server, version, build)?;
+    if version == server.version && build == server.build {
+        println!(
+            "📝 {} is already on {} {version} build {build}!",
+            server.server_name, server.jar_name
+        );
+        return Ok(());
+    }
+    if server_updates::is_downgrade(server, &version) {
+        println!(
+            "⚠️ {version} is older than {}. Minecraft can't safely load worlds saved by a newer version!",
+            server.version
+        );
+        if !allow_downgrade {
+            let input = read_line("🚨 Downgrade anyway? (y/n)")?.to_lowercase();
+            if input != "y" {
+                println!("🚨 Upgrade cancelled.");
+                return Ok(());
+            }
+        }
+    }
+    println!(
+        "📝 Upgrading {} from {} build {} to {version} build {build}...",
+        server.server_name, server.version, server.build
+    );
+    let (upgraded, backup) = server_updates::upgrade(server, jar, &version, &build)?;
+    println!(
+        "📝 Upgraded {} to {} {}! Backup taken: {}",
+        upgraded.server_name, upgraded.jar_name, upgraded.version, backup.id
+    );
+    Ok(())
+}
+
+fn apply_manifest(path: &Path, plan_only: bool, config: &mut Config) -> Result<(), Error> {
+    let manifest = Manifest::load(path)?;
+    let jars = load()?;
+    let jar = jars
+        .get_jar(&manifest.jar)
+        .ok_or(Error::ResourceNotFound(format!("Jar {} not found", manifest.jar)))?;
+    let registered = config.get_server(&manifest.name).is_some();
+    let changes = manifest::plan(&manifest, jar, registered)?;
+    if changes.is_empty() {
+        println!("📝 {} matches {}. Nothing to change.", manifest.name, path.display());
+        return Ok(());
+    }
+    println!("📝 Changes to {}:", manifest.name);
+    for change in &changes {
+        change.print();
+    }
+    if plan_only {
+        return Ok(());
+    }
+    let server = manifest::apply(&manifest, &changes, jar, &PluginApis::public()?, config)?;
+    println!("📝 Applied {} change(s) to {}!", changes.len(), server.server_name);
+    Ok(())
+}
+
+fn migrate_server(
+    server: &Server,
+    to: &str,
+    version: Option<&str>,
+    yes: bool,
+) -> Result<(), Error> {
+    let jars = load()?;
+    let jar = jars
+        .get_jar(to)
+        .ok_or(Error::ResourceNotFound("Jar not found".to_string()))?;
+    let (version, build) = server_updates::resolve(jar, server, version, None)?;
+    if migration::orphans_plugins(server, jar) {
+        println!(
+            "⚠️ {} doesn't load plugins. Everything in plugins/ will stop working!",
+            jar.name
+        );
+        if !yes {
+            let input = read_line("🚨 Migrate anyway? (y/n)")?.to_lowercase();
+            if input != "y" {
+                println!("🚨 Migration cancelled.");
+                return Ok(());
+            }
+        }
+    }
+    println!(
+        "📝 Migrating {} from {} to {} {version} build {build}...",
+        server.server_name, server.jar_name, jar.name
+    );
+    let report = migration::migrate(server, jar, &version, &build)?;
+    for (from, to) in &report.moved {
+        println!(
+            "  🌍 Moved {} to {}",
+            from.strip_prefix(&server.location).unwrap_or(from).display(),
+            to.strip_prefix(&server.location).unwrap_or(to).display()
+        );
+    }
+    if !report.unused_configs.is_empty() {
+        println!(
+            "⚠️ {} doesn't read {}. They were left in place.",
+            jar.name,
+            report.unused_configs.join(", ")
+        );
+    }
+    println!(
+        "📝 Migrated {} to {} {}! Backup taken: {}",
+        report.server.server_name, report.server.jar_name, report.server.version, report.backup.id
+    );
+    Ok(())
+}
+
+fn sync_player_lists(args: SyncArgs, config: &Config) -> Result<(), Error> {
+    let source = config
+        .get_server(&args.source)
+        .ok_or(Error::ResourceNotFound("Source server not found".to_string()))?;
+    let mut targets: Vec<Server> = Vec::new();
+    for target in &args.targets {
+        let server = config
+            .get_server(target)
+            .ok_or(Error::ResourceNotFound(format!("Server {target} not found")))?;
+        targets.push(server);
+    }
+    if let Some(tag) = args.tag {
+        let tag = tag.to_lowercase();
+        for info in &config.servers {
+            let Some(server) = config.get_server(&info.server_name) else {
+                continue;
+            };
+            if server.tags.contains(&tag) {
+                targets.push(server);
+            }
+        }
+    }
+    let mut seen = vec![source.location.clone()];
+    targets.retain(|s| {
+        if seen.contains(&s.location) {
+            return false;
+        }
+        seen.push(s.location.clone());
+        true
+    });
+    if targets.is_empty() {
+        return Err(Error::ResourceNotFound(
+            "No target servers to sync to".to_string(),
+        ));
+    }
+    let lists = if args.lists.is_empty() {
+        PlayerList::all()
+    } else {
+        args.lists
+    };
+    println!(
+        "📝 Syncing player lists from {} to {} server(s)...{}",
+        source.server_name,
+        targets.len(),
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+    let reports = player_lists::sync(&source, &targets, &lists, args.mode, args.dry_run)?;
+    for report in &reports {
+        report.print();
+    }
+    let conflicts: usize = reports.iter().map(|r| r.conflicts.len()).sum();
+    if conflicts > 0 {
+        println!("⚠️ {conflicts} conflict(s) found, the source server's entries win.");
+    }
+    if args.dry_run {
+        println!("📝 Dry run finished, no files were changed.");
+    } else {
+        println!("📝 Synced player lists!");
     }
     Ok(())
 }
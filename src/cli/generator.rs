@@ -1,79 +1,277 @@
+use crate::cli::constructor::GenerateArgs;
 use crate::config::Config;
 use crate::error::Error;
-use crate::minecraft::jars::load;
+use crate::minecraft::jars::{load, Jar};
+use crate::minecraft::memory;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::templates::Templates;
 use crate::utils::{canonize, read_line};
 use inquire::Select;
 use std::fs;
-use std::path::Path;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
-pub fn prompt_wizard(config: &mut Config) -> Result<(), Error> {
-    let jars = load()?;
-    println!("🎚️ Welcome to the server generator");
-    let mut server_name;
+// Fails with the flag to pass instead of prompting when there is no one to ask
+fn missing(flag: &str, what: &str) -> Error {
+    Error::ResourceNotFound(format!(
+        "No {what} given, pass {flag} (prompts are disabled with --non-interactive or without a terminal)"
+    ))
+}
+
+fn check_name(config: &Config, name: &str) -> Result<(), Error> {
+    if config.get_server(name).is_some() {
+        return Err(Error::ResourceNotFound(format!(
+            "A server named {name} already exists"
+        )));
+    }
+    if !(1..=100).contains(&name.len()) {
+        return Err(Error::ResourceNotFound(
+            "Server name must be within 1 and 100 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Resolves `latest` to the newest version and checks that the version exists.
+pub fn resolve_version(jar: &Jar, version: &str) -> Result<String, Error> {
+    let versions = jar.get_versions()?;
+    if version.eq_ignore_ascii_case("latest") {
+        return versions
+            .first()
+            .cloned()
+            .ok_or(Error::ResourceNotFound("No versions found".to_string()));
+    }
+    if !versions.iter().any(|v| v == version) {
+        return Err(Error::ResourceNotFound(format!(
+            "{} has no version {version}",
+            jar.name
+        )));
+    }
+    Ok(version.to_string())
+}
+
+/// Resolves `latest` (or no build) to the newest build of the version.
+pub fn resolve_build(jar: &Jar, version: &str, build: Option<&str>) -> Result<String, Error> {
+    match build {
+        Some(build) if !build.eq_ignore_ascii_case("latest") => Ok(build.to_string()),
+        _ => jar
+            .get_latest_build(version)
+            .map(|build| build.to_string())
+            .ok_or(Error::ResourceNotFound("Jar build not found".to_string())),
+    }
+}
+
+fn prompt_name(config: &Config) -> Result<String, Error> {
     loop {
-        server_name = read_line("🎚️ Please enter the server name:")?;
-        if config.get_server(&server_name).is_some() {
-            println!(
-                "⚠️ A server with the same name already exists! Please enter a different name:"
-            );
-            continue;
+        let server_name = read_line("🎚️ Please enter the server name:")?;
+        match check_name(config, &server_name) {
+            Ok(()) => return Ok(server_name),
+            Err(e) => println!("⚠️ {e}. Please enter a different name:"),
         }
-        let length = server_name.len() as u8;
-        if !(1..=100).contains(&length) {
-            println!(
-                "⚠️ Server name must be within 1 and 100 characters. Please enter a different name:"
-            );
-            server_name = read_line("🎚️ Please enter the server name:")?;
+    }
+}
+
+fn prompt_location(
+    create_dir: bool,
+    interactive: bool,
+    location: Option<PathBuf>,
+) -> Result<PathBuf, Error> {
+    let mut location = match location {
+        Some(location) => location,
+        None if !interactive => return Err(missing("--location", "server location")),
+        None => {
+            let location = read_line("🎚️ Please enter the server location:")?;
+            if location.is_empty() {
+                // Set path to location where the command was run
+                let current = std::env::current_dir()?;
+                println!(
+                    "🎚️ Using current directory as server location ({}).",
+                    current.display()
+                );
+                current
+            } else {
+                PathBuf::from(location)
+            }
+        }
+    };
+    while !location.exists() {
+        if create_dir {
+            fs::create_dir_all(&location)?;
+            break;
+        }
+        if !interactive {
+            return Err(Error::ResourceNotFound(format!(
+                "{} does not exist, pass --create-dir to create it",
+                location.display()
+            )));
         }
-        break;
-    }
-    let jar_name = Select::new(
-        "🎚️ Please enter the server Jar",
-        jars.jars
-            .iter()
-            .map(|j| j.name.as_str())
-            .collect::<Vec<&str>>(),
-    )
-    .prompt()?;
-    let jar = jars
-        .get_jar(jar_name)
-        .ok_or(Error::ResourceNotFound("Jar not found".to_string()))?;
-    let version = Select::new("🎚️ Please enter the server version", jar.get_versions()?).prompt()?;
-    let builds = jar.get_builds(&version)?;
-    let latest = builds
-        .first()
-        .ok_or(Error::ResourceNotFound("Jar build not found".to_string()))?;
-    let build = Select::new(
-        &format!("🎚️ Please enter the jar build ({} is latest)", latest),
-        builds,
-    )
-    .prompt()?
-    .to_string();
-    let mut location = read_line("🎚️ Please enter the server location:")?;
-    let mut path = Path::new(&location);
-    if location.is_empty() {
-        // Set path to location where the command was run
-        location = std::env::current_dir()?.to_str().unwrap().to_string();
-        path = Path::new(&location);
-        println!(
-            "🎚️ Using current directory as server location ({}).",
-            location
-        );
-    }
-    while !path.exists() {
         println!("🚨 Path does not exist! Should we create it?");
         let should_create = read_line("🎚️ Create dir (Y/N):")?;
         if should_create.to_lowercase() == "y" {
-            fs::create_dir_all(path)?;
+            fs::create_dir_all(&location)?;
             break;
         }
-        location = read_line("🎚️ Please enter the server location:")?;
-        path = Path::new(&location);
+        location = PathBuf::from(read_line("🎚️ Please enter the server location:")?);
+    }
+    canonize(&location)
+}
+
+// Heap sizes from the flags, or asked for with the suggestion as the default
+fn prompt_memory(
+    args: &GenerateArgs,
+    interactive: bool,
+) -> Result<Option<(String, String)>, Error> {
+    if args.min_memory.is_some() || args.max_memory.is_some() {
+        let min = args.min_memory.as_deref().unwrap_or(memory::DEFAULT_HEAP);
+        let max = args.max_memory.as_deref().unwrap_or(memory::DEFAULT_HEAP);
+        return memory::validate(min, max).map(Some);
+    }
+    if !interactive {
+        return Ok(None);
+    }
+    let suggestion = memory::host_memory().map_or_else(
+        || memory::DEFAULT_HEAP.to_string(),
+        |host| memory::suggest(host.available),
+    );
+    loop {
+        let max = read_line(&format!(
+            "🎚️ Please enter the server memory ({suggestion} if empty):"
+        ))?;
+        let max = if max.is_empty() {
+            suggestion.clone()
+        } else {
+            max
+        };
+        match memory::validate(&max, &max) {
+            Ok(sizes) => return Ok(Some(sizes)),
+            Err(e) => println!("⚠️ {e}"),
+        }
+    }
+}
+
+fn prompt_port(port: Option<u16>, interactive: bool) -> Result<Option<u16>, Error> {
+    if port.is_some() || !interactive {
+        return Ok(port);
+    }
+    loop {
+        let port = read_line("🎚️ Please enter the server port (25565 if empty):")?;
+        if port.is_empty() {
+            return Ok(None);
+        }
+        match port.parse::<u16>() {
+            Ok(port) => return Ok(Some(port)),
+            Err(_) => println!("⚠️ {port} is not a valid port"),
+        }
+    }
+}
+
+pub fn prompt_wizard(config: &mut Config, args: GenerateArgs) -> Result<(), Error> {
+    let interactive = !args.non_interactive && std::io::stdin().is_terminal();
+    let jars = load()?;
+    let templates = Templates::open()?;
+    let template = args
+        .template
+        .as_deref()
+        .map(|name| templates.get(name))
+        .transpose()?;
+    // What the template already sets isn't asked for
+    let template_sets = |key: &str| {
+        template
+            .as_ref()
+            .is_some_and(|t| t.server.contains_key(key))
+    };
+    if interactive {
+        println!("🎚️ Welcome to the server generator");
+    }
+    let server_name = match &args.name {
+        Some(name) => {
+            check_name(config, name)?;
+            name.clone()
+        }
+        None if !interactive => return Err(missing("--name", "server name")),
+        None => prompt_name(config)?,
+    };
+    let jar_name = match &args.jar {
+        Some(jar) => jar.clone(),
+        None if !interactive => return Err(missing("--jar", "jar")),
+        None => Select::new(
+            "🎚️ Please enter the server Jar",
+            jars.jars
+                .iter()
+                .map(|j| j.name.as_str())
+                .collect::<Vec<&str>>(),
+        )
+        .prompt()?
+        .to_string(),
+    };
+    let jar = jars
+        .get_jar(&jar_name)
+        .ok_or(Error::ResourceNotFound(format!(
+            "Jar {jar_name} not found (available: {})",
+            jars.jars
+                .iter()
+                .map(|j| j.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))?;
+    let version = match &args.version {
+        Some(version) => resolve_version(jar, version)?,
+        None if !interactive => return Err(missing("--version", "version")),
+        None => Select::new("🎚️ Please enter the server version", jar.get_versions()?).prompt()?,
+    };
+    let build = if args.build.is_some() || !interactive {
+        resolve_build(jar, &version, args.build.as_deref())?
+    } else {
+        let builds = jar.get_builds(&version)?;
+        let latest = builds
+            .first()
+            .ok_or(Error::ResourceNotFound("Jar build not found".to_string()))?;
+        Select::new(
+            &format!("🎚️ Please enter the jar build ({} is latest)", latest),
+            builds,
+        )
+        .prompt()?
+        .to_string()
+    };
+    let full_path = prompt_location(args.create_dir, interactive, args.location.clone())?;
+    let sizes = prompt_memory(&args, interactive && !template_sets("xmx"))?;
+    let port = prompt_port(args.port, interactive)?;
+    let accept_eula = args.accept_eula
+        || (interactive
+            && !template_sets("accept_eula")
+            && read_line("🎚️ Accept the Minecraft EULA (https://aka.ms/MinecraftEULA)? (y/n)")?
+                .eq_ignore_ascii_case("y"));
+
+    let mut server = jar.download(&version, &build, &server_name, full_path.as_path())?;
+    if let Some(template) = &template {
+        println!("🎚️ Laying down template {}...", template.name);
+        server = templates.apply(template, &server)?;
+    }
+    if let Some((min, max)) = sizes {
+        server.xms = Some(min);
+        server.xmx = Some(max);
+        server.write();
+    }
+    if let Some(port) = port {
+        let manipulator = ServerManipulator {
+            server: server.clone(),
+        };
+        manipulator.set_property("server-port", &port.to_string())?;
+    }
+    if accept_eula {
+        server.remember_eula()?;
     }
-    let full_path = canonize(path)?;
-    let server = jar.download(&version, &build, &server_name, full_path.as_path())?;
-    println!("🎛️ Server generated!");
     config.add_server(&server, true);
     println!("🎛️ Server generated!");
     Ok(())
 }
+
+// Location for `create`, which only prompts when a terminal is attached
+pub fn create_location(location: &str, create_dir: bool) -> Result<PathBuf, Error> {
+    let interactive = std::io::stdin().is_terminal();
+    prompt_location(
+        create_dir,
+        interactive,
+        Some(Path::new(location).to_path_buf()),
+    )
+}
@@ -0,0 +1,83 @@
+use crate::error::Error;
+use crate::java::install::{self, java_dir, Adoptium, ImageType};
+use crate::java::runtime::{self, JavaRuntime};
+use crate::utils::{colorize, Color};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum JavaAction {
+    /// Download a Temurin JDK (or JRE) from Adoptium for servers to use
+    Install {
+        /// Major Java version (8, 17, 21...)
+        version: u32,
+        /// Install the smaller JRE instead of the JDK
+        #[clap(long)]
+        jre: bool,
+    },
+    /// List Java runtimes boxes can use
+    List,
+    /// Remove a runtime installed by boxes
+    Remove {
+        /// Major version or full version (21, 21.0.1+12)
+        version: String,
+    },
+}
+
+fn print_runtime(runtime: &JavaRuntime, managed: bool) {
+    println!(
+        "  ☕ Java {} ({}{}) {}",
+        colorize(&runtime.version, Color::Gold),
+        runtime.vendor.as_deref().unwrap_or("Unknown vendor"),
+        if managed { ", installed by boxes" } else { "" },
+        runtime.home.display()
+    );
+}
+
+pub fn manage_java_action(action: JavaAction) -> Result<(), Error> {
+    let root = java_dir()?;
+    match action {
+        JavaAction::Install { version, jre } => {
+            let image = if jre { ImageType::Jre } else { ImageType::Jdk };
+            let adoptium = Adoptium::public()?;
+            let package = adoptium.find(version, image)?;
+            std::fs::create_dir_all(&root)?;
+            let runtime = adoptium.install(&package, &root)?;
+            println!(
+                "☕ Installed Java {} to {}!",
+                colorize(&runtime.version, Color::Gold),
+                runtime.home.display()
+            );
+        }
+        JavaAction::List => {
+            let runtimes = runtime::discover();
+            println!("📝 Java runtimes:");
+            if runtimes.is_empty() {
+                println!(
+                    "      No Java runtimes found! Install one with boxes java install <version>"
+                );
+            }
+            for runtime in &runtimes {
+                print_runtime(runtime, runtime.home.starts_with(&root));
+            }
+        }
+        JavaAction::Remove { version } => {
+            let matching: Vec<JavaRuntime> = install::installed(&root)
+                .into_iter()
+                .filter(|r| r.version == version || r.major.to_string() == version)
+                .collect();
+            if matching.is_empty() {
+                return Err(Error::ResourceNotFound(format!(
+                    "Java {version} isn't installed by boxes"
+                )));
+            }
+            for runtime in &matching {
+                install::remove(&root, runtime)?;
+                println!(
+                    "☕ Removed Java {}",
+                    colorize(&runtime.version, Color::Gold)
+                );
+            }
+        }
+    }
+    Ok(())
+}
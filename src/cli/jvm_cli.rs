@@ -0,0 +1,80 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::jvm::{shell_join, JvmProfile};
+use crate::utils::{colorize, Color};
+use clap::{Args, Subcommand, ValueEnum};
+
+#[derive(Args, Debug)]
+pub struct JvmArgs {
+    pub name: String,
+    #[command(subcommand)]
+    pub action: JvmAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JvmAction {
+    /// Print the flag profile and the exact command line boxes runs
+    Show,
+    /// Change the flag profile or the extra arguments
+    Set {
+        /// Flag profile (auto picks one from the jar and heap size)
+        #[clap(short, long, value_enum)]
+        profile: Option<JvmProfile>,
+        /// Extra JVM argument, added before -jar (repeatable)
+        #[clap(long = "jvm-arg", allow_hyphen_values = true)]
+        jvm_args: Vec<String>,
+        /// Extra server argument, added after the jar (repeatable)
+        #[clap(long = "arg", allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Remove the extra arguments set before
+        #[clap(long)]
+        clear_args: bool,
+    },
+}
+
+pub fn manage_jvm_action(args: JvmArgs, config: &mut Config) -> Result<(), Error> {
+    let mut server = config
+        .get_server(&args.name)
+        .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+    if let JvmAction::Set {
+        profile,
+        jvm_args,
+        args,
+        clear_args,
+    } = args.action
+    {
+        let settings = &mut server.jvm;
+        if let Some(profile) = profile {
+            settings.profile = profile;
+        }
+        if clear_args {
+            settings.jvm_args.clear();
+            settings.args.clear();
+        }
+        settings.jvm_args.extend(jvm_args);
+        settings.args.extend(args);
+        server.write();
+        println!("📝 Saved JVM settings of {}!", server.server_name);
+    }
+    let profile = server
+        .jvm
+        .profile
+        .resolve(&server.jar_name, server.xmx.as_deref());
+    let name = |profile: JvmProfile| {
+        profile
+            .to_possible_value()
+            .map_or_else(String::new, |value| value.get_name().to_string())
+    };
+    println!(
+        "☕ Profile: {}{}",
+        colorize(&name(server.jvm.profile), Color::Gold),
+        if profile == server.jvm.profile {
+            String::new()
+        } else {
+            format!(" ({})", name(profile))
+        }
+    );
+    println!("☕ Command line:");
+    println!("{}", shell_join(&server.command_line()?));
+    Ok(())
+}
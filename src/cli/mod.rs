@@ -1,4 +1,11 @@
 pub mod constructor;
 pub(crate) mod generator;
 pub(crate) mod executor;
+mod backup_cli;
 mod config_cli;
+mod java_cli;
+mod jvm_cli;
+mod network_cli;
+mod plugin_cli;
+mod template_cli;
+mod trash_cli;
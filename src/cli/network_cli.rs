@@ -0,0 +1,119 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::instances;
+use crate::minecraft::network::{self, Network};
+use crate::minecraft::server::Server;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum NetworkAction {
+    /// Wire a Velocity proxy to its backends (ports, velocity.toml, forwarding secret)
+    Create {
+        name: String,
+        /// Server running Velocity
+        #[clap(long)]
+        proxy: String,
+        /// Backend servers, in the order players are sent to them
+        #[clap(long = "backend", required = true)]
+        backends: Vec<String>,
+    },
+    /// List networks
+    List,
+    /// Start the backends, then the proxy
+    Start {
+        name: String,
+        /// Accept the EULA of backends that haven't yet
+        #[clap(long)]
+        accept_eula: bool,
+    },
+    /// Stop the proxy, then the backends
+    Stop { name: String },
+}
+
+fn get_server(config: &Config, name: &str) -> Result<Server, Error> {
+    config
+        .get_server(name)
+        .ok_or(Error::ResourceNotFound(format!("Server {name} not found")))
+}
+
+// The proxy and backends of a network
+fn servers(config: &Config, name: &str) -> Result<(Server, Vec<Server>), Error> {
+    let network = config
+        .networks
+        .iter()
+        .find(|n| n.name.eq_ignore_ascii_case(name))
+        .ok_or(Error::ResourceNotFound(format!("Network {name} not found")))?;
+    let backends = network
+        .backends
+        .iter()
+        .map(|backend| get_server(config, backend))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((get_server(config, &network.proxy)?, backends))
+}
+
+pub fn manage_network_action(action: NetworkAction, config: &mut Config) -> Result<(), Error> {
+    match action {
+        NetworkAction::Create {
+            name,
+            proxy,
+            backends,
+        } => {
+            let proxy = get_server(config, &proxy)?;
+            let backends = backends
+                .iter()
+                .map(|backend| get_server(config, backend))
+                .collect::<Result<Vec<_>, _>>()?;
+            let members: Vec<&str> = std::iter::once(&proxy)
+                .chain(&backends)
+                .map(|s| s.server_name.as_str())
+                .collect();
+            let taken: Vec<u16> = config
+                .servers
+                .iter()
+                .filter(|info| !members.contains(&info.server_name.as_str()))
+                .filter_map(|info| config.get_server(&info.server_name))
+                .flat_map(|server| instances::ports(&server))
+                .collect();
+            println!("🕸️ Wiring network {name}...");
+            let wiring = network::wire(&proxy, &backends, &taken)?;
+            println!(
+                "  🔀 {} (proxy) on port {}",
+                proxy.server_name, wiring.proxy_port
+            );
+            for (backend, port) in &wiring.backend_ports {
+                println!("  📦 {backend} on port {port}");
+            }
+            let network = Network {
+                name: name.clone(),
+                proxy: proxy.server_name.clone(),
+                backends: backends.iter().map(|b| b.server_name.clone()).collect(),
+            };
+            config
+                .networks
+                .retain(|n| !n.name.eq_ignore_ascii_case(&name));
+            config.networks.push(network);
+            config.save()?;
+            println!("🕸️ Network {name} created!");
+        }
+        NetworkAction::List => {
+            println!("🕸️ Networks:");
+            if config.networks.is_empty() {
+                println!("      No networks!");
+            }
+            for network in &config.networks {
+                network.print();
+            }
+        }
+        NetworkAction::Start { name, accept_eula } => {
+            let (proxy, backends) = servers(config, &name)?;
+            network::start(&proxy, &backends, accept_eula)?;
+            println!("🕸️ Network {name} is up!");
+        }
+        NetworkAction::Stop { name } => {
+            let (proxy, backends) = servers(config, &name)?;
+            network::stop(&proxy, &backends)?;
+            println!("🕸️ Network {name} stopped!");
+        }
+    }
+    Ok(())
+}
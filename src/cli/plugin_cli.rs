@@ -0,0 +1,183 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::plugin_lock::PluginLock;
+use crate::minecraft::plugin_sources::{self, PluginApis, PluginSource};
+use crate::minecraft::plugin_updates;
+use crate::minecraft::plugins;
+use crate::minecraft::server::Server;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum PluginAction {
+    /// Install a plugin and its dependencies from Modrinth or Hangar (slug[@version])
+    Install {
+        plugin: String,
+        /// Only look for the plugin on this source
+        #[clap(short, long, value_enum)]
+        source: Option<PluginSource>,
+    },
+    /// Bring the plugins folder in line with plugins.lock
+    Sync {
+        /// Use the lockfile of another server instead
+        #[clap(short, long)]
+        from: Option<String>,
+    },
+    /// List plugins with a newer compatible release
+    Outdated,
+    /// Upgrade outdated plugins, keeping the old jars in plugins/.rollback
+    Upgrade {
+        /// Name, project or file name of the plugin to upgrade
+        #[clap(required_unless_present = "all")]
+        plugin: Option<String>,
+        /// Upgrade every outdated plugin
+        #[clap(short, long, conflicts_with = "plugin")]
+        all: bool,
+    },
+    /// Move a plugin to plugins/.disabled so the server stops loading it
+    Disable {
+        /// Plugin name (from plugin.yml) or file name
+        plugin: String,
+        /// Change the plugins folder even while the server is running
+        #[clap(short, long)]
+        force: bool,
+    },
+    /// Move a disabled plugin back into plugins
+    Enable {
+        /// Plugin name (from plugin.yml) or file name
+        plugin: String,
+        /// Change the plugins folder even while the server is running
+        #[clap(short, long)]
+        force: bool,
+    },
+    /// Delete a plugin jar
+    Remove {
+        /// Plugin name (from plugin.yml) or file name
+        plugin: String,
+        /// Change the plugins folder even while the server is running
+        #[clap(short, long)]
+        force: bool,
+    },
+}
+
+pub fn manage_plugin_action(
+    server: &Server,
+    action: Option<PluginAction>,
+    config: &Config,
+) -> Result<(), Error> {
+    match action {
+        None => {
+            let (plugins, issues) = plugins::inspect(server);
+            println!(
+                "📝 Getting plugins for {} ({} plugin(s))...",
+                server.server_name,
+                plugins.len()
+            );
+            for plugin in &plugins {
+                plugin.print();
+            }
+            for issue in &issues {
+                issue.print();
+            }
+        }
+        Some(PluginAction::Install { plugin, source }) => {
+            println!("📝 Installing {plugin} on {}...", server.server_name);
+            let apis = PluginApis::public()?;
+            let installed = plugin_sources::install(server, &plugin, source, &apis)?;
+            println!("📝 Installed {} plugin(s)!", installed.len());
+        }
+        Some(PluginAction::Sync { from }) => {
+            let lock = match from {
+                Some(from) => {
+                    let source = config
+                        .get_server(&from)
+                        .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+                    println!("📝 Copying plugins.lock from {}...", source.server_name);
+                    let lock = PluginLock::load(&source)?;
+                    lock.save(server)?;
+                    lock
+                }
+                None => PluginLock::load(server)?,
+            };
+            if lock.plugins.is_empty() {
+                println!("🚨 plugins.lock is empty, nothing to sync!");
+                return Ok(());
+            }
+            println!(
+                "📝 Syncing {} plugin(s) on {}...",
+                lock.plugins.len(),
+                server.server_name
+            );
+            let report = lock.sync(server, &PluginApis::public()?)?;
+            report.print();
+            println!("📝 Synced plugins!");
+        }
+        Some(PluginAction::Disable { plugin, force }) => {
+            ensure_stopped(server, force)?;
+            let file = plugins::disable(server, &plugin)?;
+            println!("📝 Disabled {file}! It was moved to plugins/.disabled");
+        }
+        Some(PluginAction::Enable { plugin, force }) => {
+            ensure_stopped(server, force)?;
+            let file = plugins::enable(server, &plugin)?;
+            println!("📝 Enabled {file}!");
+        }
+        Some(PluginAction::Remove { plugin, force }) => {
+            ensure_stopped(server, force)?;
+            let file = plugins::remove(server, &plugin)?;
+            let mut lock = PluginLock::load(server)?;
+            if lock.remove_file(&file).is_some() {
+                lock.save(server)?;
+                println!("📝 Removed {file} from plugins.lock");
+            }
+        }
+        Some(PluginAction::Outdated) => {
+            println!(
+                "📝 Checking plugins of {} for updates...",
+                server.server_name
+            );
+            let updates = plugin_updates::check(server, &PluginApis::public()?)?;
+            if updates.is_empty() {
+                println!("🚨 No plugins with a known source found!");
+                return Ok(());
+            }
+            plugin_updates::print_table(&updates);
+            let outdated = updates.iter().filter(|u| u.is_outdated()).count();
+            println!("📝 {outdated} plugin(s) can be upgraded.");
+        }
+        Some(PluginAction::Upgrade { plugin, all }) => {
+            let apis = PluginApis::public()?;
+            let updates = plugin_updates::check(server, &apis)?;
+            let selected: Vec<_> = match &plugin {
+                Some(plugin) => {
+                    let update = updates.iter().find(|u| u.matches(plugin)).ok_or(
+                        Error::ResourceNotFound(format!(
+                            "{plugin} is not a plugin with a known source"
+                        )),
+                    )?;
+                    vec![update]
+                }
+                None => updates.iter().collect(),
+            };
+            let selected: Vec<_> = selected.into_iter().filter(|u| u.is_outdated()).collect();
+            if selected.is_empty() {
+                println!("📝 Everything is up to date!");
+                return Ok(());
+            }
+            let rollback = plugin_updates::upgrade(server, &selected, &apis)?;
+            println!(
+                "📝 Upgraded {} plugin(s)! Old jars were moved to {}",
+                selected.len(),
+                rollback.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn ensure_stopped(server: &Server, force: bool) -> Result<(), Error> {
+    if !force && server.is_running() {
+        println!("🚨 Use --force to change plugins while the server is running");
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    Ok(())
+}
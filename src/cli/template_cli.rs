@@ -0,0 +1,52 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::templates::Templates;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateAction {
+    /// List templates
+    List,
+    /// Make a template of a server (settings, properties and files without worlds)
+    CreateFrom {
+        server: String,
+        /// Template name (defaults to the server name)
+        name: Option<String>,
+    },
+    /// Delete a template
+    Delete { name: String },
+}
+
+pub fn manage_template_action(action: TemplateAction, config: &Config) -> Result<(), Error> {
+    let templates = Templates::open()?;
+    match action {
+        TemplateAction::List => {
+            let list = templates.list()?;
+            println!("📝 Templates ({}):", templates.root.display());
+            if list.is_empty() {
+                println!("      No templates!");
+            }
+            for template in &list {
+                template.print();
+            }
+        }
+        TemplateAction::CreateFrom { server, name } => {
+            let server = config
+                .get_server(&server)
+                .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+            let name = name.unwrap_or_else(|| server.server_name.clone());
+            println!("📝 Making template {name} from {}...", server.server_name);
+            let template = templates.create_from(&server, &name)?;
+            println!(
+                "📝 Created template {}! Files are in {}",
+                template.name,
+                templates.files(&template.name)?.display()
+            );
+        }
+        TemplateAction::Delete { name } => {
+            templates.delete(&name)?;
+            println!("📝 Deleted template {name}!");
+        }
+    }
+    Ok(())
+}
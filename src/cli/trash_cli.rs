@@ -0,0 +1,94 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::minecraft::trash::Trash;
+use crate::utils::{format_size, read_line};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum TrashAction {
+    /// List deleted servers
+    List,
+    /// Move a deleted server back and add it to the config again
+    Restore {
+        /// Trash id, or the name of the server
+        server: String,
+    },
+    /// Permanently delete servers in the trash
+    Empty {
+        /// Only delete this server (trash id or name)
+        server: Option<String>,
+        /// Skip typing the server name (or "empty" for the whole trash) to confirm
+        #[clap(short, long)]
+        yes: bool,
+    },
+}
+
+pub fn manage_trash_action(action: TrashAction, config: &mut Config) -> Result<(), Error> {
+    let trash = Trash::open()?;
+    match action {
+        TrashAction::List => {
+            let entries = trash.list()?;
+            println!("📝 Trash ({}):", trash.root.display());
+            if entries.is_empty() {
+                println!("      Nothing in the trash!");
+            }
+            for entry in &entries {
+                entry.print();
+            }
+        }
+        TrashAction::Restore { server } => {
+            let entry = trash.find(&server)?;
+            if config.get_server(&entry.server_name).is_some() {
+                return Err(Error::ResourceNotFound(format!(
+                    "A server named {} already exists",
+                    entry.server_name
+                )));
+            }
+            println!("📝 Restoring {} from the trash...", entry.server_name);
+            trash.restore(&entry)?;
+            let server = Server::from_path(&entry.location.to_string_lossy())?;
+            config.add_server(&server, true);
+            println!(
+                "📝 Restored {} to {}!",
+                entry.server_name,
+                entry.location.display()
+            );
+        }
+        TrashAction::Empty { server, yes } => {
+            let (entries, confirmation) = match server {
+                Some(server) => {
+                    let entry = trash.find(&server)?;
+                    let name = entry.server_name.clone();
+                    (vec![entry], name)
+                }
+                None => (trash.list()?, "empty".to_string()),
+            };
+            if entries.is_empty() {
+                println!("📝 Nothing in the trash!");
+                return Ok(());
+            }
+            if !yes {
+                let typed = read_line(&format!(
+                    "🚨 Type {confirmation} to confirm permanently deleting {} server(s):",
+                    entries.len()
+                ))?;
+                if typed.trim() != confirmation {
+                    println!("🚨 Confirmation does not match, nothing was deleted.");
+                    return Ok(());
+                }
+            }
+            let mut freed = 0;
+            for entry in &entries {
+                trash.remove(entry)?;
+                freed += entry.size;
+            }
+            println!(
+                "📝 Permanently deleted {} server(s), freeing {}.",
+                entries.len(),
+                format_size(freed)
+            );
+        }
+    }
+    Ok(())
+}
@@ -9,7 +9,8 @@
 
 use crate::error::Error;
 use crate::minecraft::jars;
-use crate::minecraft::server::Server;
+use crate::minecraft::network::Network;
+use crate::minecraft::server::{is_running, Server};
 use crate::utils::{colorize, Color};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -19,6 +20,8 @@ use std::process::Command;
 #[derive(Deserialize, Serialize, Clone, Default)]
 pub struct Config {
     pub servers: Vec<ServerInfo>,
+    #[serde(default)]
+    pub networks: Vec<Network>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -46,9 +49,7 @@ impl Config {
         let running_servers = Command::new("jps").arg("-v").output()?;
         let running_servers = String::from_utf8(running_servers.stdout).unwrap_or_default();
         for server in &self.servers {
-            let running =
-                running_servers.contains(format!("-Dname={}", server.server_name.trim()).as_str());
-            let running = if running {
+            let running = if is_running(&running_servers, &server.server_name) {
                 colorize("Running", Color::Green)
             } else {
                 colorize("Stopped", Color::Red)
@@ -85,11 +86,20 @@ impl Config {
             }
         }
         if changed {
-            confy::store("boxes", None, config.clone())?;
+            config.save()?;
         }
         Ok(config)
     }
 
+    // Writes to a temporary file that replaces the config, so it is never left half written
+    pub fn save(&self) -> Result<(), Error> {
+        let path = confy::get_configuration_file_path("boxes", None)?;
+        let temp = path.with_extension("toml.tmp");
+        confy::store_path(&temp, self)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
     pub fn delete() -> Result<(), Error> {
         println!("📝 Deleting config...");
         let path = confy::get_configuration_file_path("config", None)?;
@@ -114,7 +124,7 @@ impl Config {
         let server_info = ServerInfo::from_server(server);
         self.servers.push(server_info);
         if save {
-            confy::store("boxes", None, self).expect("🚨 Config file could not be saved!");
+            self.save().expect("🚨 Config file could not be saved!");
         }
         println!("📝 Added server to config!");
     }
@@ -138,8 +148,30 @@ impl Config {
         self.servers.remove(index);
         let server_info = ServerInfo::from_server(server);
         self.servers.push(server_info);
-        confy::store("boxes", None, self)?;
+        self.save()?;
         println!("📝 Saved server to config!");
         Ok(())
     }
+
+    // Replaces the entry of a server that was renamed or moved
+    pub fn update_server(&mut self, old_name: &str, server: &Server) -> Result<(), Error> {
+        let info = self
+            .servers
+            .iter_mut()
+            .find(|s| s.server_name.eq_ignore_ascii_case(old_name))
+            .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+        *info = ServerInfo::from_server(server);
+        self.save()
+    }
+
+    pub fn remove_server(&mut self, server_name: &str) -> Result<ServerInfo, Error> {
+        let index = self
+            .servers
+            .iter()
+            .position(|s| s.server_name.eq_ignore_ascii_case(server_name))
+            .ok_or(Error::ResourceNotFound("Server not found".to_string()))?;
+        let removed = self.servers.remove(index);
+        self.save()?;
+        Ok(removed)
+    }
 }
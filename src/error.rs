@@ -8,6 +8,14 @@ pub enum Error {
     Reqwest(#[from] reqwest::Error),
     #[error("Toml error: {0}")]
     Toml(#[from] toml::de::Error),
+    #[error("Toml serialization error: {0}")]
+    TomlSer(#[from] toml::ser::Error),
+    #[error("Json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Yaml error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Unknown error")]
@@ -15,4 +23,14 @@ pub enum Error {
 
     #[error("🚨 Resource not found: {0}")]
     ResourceNotFound(String),
-}
\ No newline at end of file
+    #[error("🚨 Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("🚨 Server {0} is running! Stop it first")]
+    ServerRunning(String),
+    #[error("🚨 Console error: {0}")]
+    Console(String),
+    #[error("🚨 No Java {0} runtime found! Run `boxes java install` or set java_path in server_box.toml")]
+    JavaNotFound(String),
+    #[error("🚨 Hash mismatch: expected {expected}, got {actual}")]
+    HashMismatch { expected: String, actual: String },
+}
@@ -0,0 +1,234 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::java::runtime::{from_homes, runtime_homes, JavaRuntime};
+use crate::utils::{data_dir, http_client, verify_hash};
+use flate2::read::GzDecoder;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Where `boxes java install` puts runtimes, one folder per release.
+pub fn java_dir() -> Result<PathBuf, Error> {
+    Ok(data_dir()?.join("java"))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ImageType {
+    #[default]
+    Jdk,
+    Jre,
+}
+
+impl ImageType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Jdk => "jdk",
+            Self::Jre => "jre",
+        }
+    }
+}
+
+// Operating system and architecture names used by the Adoptium API
+pub fn host_platform() -> Result<(&'static str, &'static str), Error> {
+    let os = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "mac",
+        "windows" => "windows",
+        os => {
+            return Err(Error::ResourceNotFound(format!(
+                "No Temurin builds for {os}"
+            )))
+        }
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "x86" => "x86",
+        "aarch64" => "aarch64",
+        "arm" => "arm",
+        "powerpc64" => "ppc64le",
+        "s390x" => "s390x",
+        arch => {
+            return Err(Error::ResourceNotFound(format!(
+                "No Temurin builds for {arch}"
+            )))
+        }
+    };
+    Ok((os, arch))
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    binary: AssetBinary,
+    release_name: String,
+}
+
+#[derive(Deserialize)]
+struct AssetBinary {
+    package: JavaPackage,
+}
+
+/// A Temurin archive with its SHA-256 checksum.
+#[derive(Deserialize, Clone, Debug)]
+pub struct JavaPackage {
+    pub name: String,
+    pub link: String,
+    pub checksum: String,
+    #[serde(skip)]
+    pub release_name: String,
+    #[serde(skip)]
+    pub image: ImageType,
+}
+
+impl JavaPackage {
+    /// Folder the package is installed to. JREs get a suffix so they don't clash with the
+    /// JDK of the same release.
+    pub fn folder(&self) -> String {
+        match self.image {
+            ImageType::Jdk => self.release_name.clone(),
+            ImageType::Jre => format!("{}-jre", self.release_name),
+        }
+    }
+}
+
+// Base URL of the Adoptium API, swappable for tests
+pub struct Adoptium {
+    pub api: String,
+    client: Client,
+}
+
+impl Adoptium {
+    pub fn new(api: &str) -> Result<Self, Error> {
+        Ok(Self {
+            api: api.trim_end_matches('/').to_string(),
+            client: http_client()?,
+        })
+    }
+
+    pub fn public() -> Result<Self, Error> {
+        Self::new("https://api.adoptium.net/v3")
+    }
+
+    /// The latest Temurin release of a Java version for this OS and architecture.
+    pub fn find(&self, major: u32, image: ImageType) -> Result<JavaPackage, Error> {
+        let (os, arch) = host_platform()?;
+        let assets: Vec<Asset> = self
+            .client
+            .get(format!("{}/assets/latest/{major}/hotspot", self.api))
+            .query(&[
+                ("architecture", arch),
+                ("image_type", image.as_str()),
+                ("os", os),
+                ("vendor", "eclipse"),
+            ])
+            .send()?
+            .error_for_status()?
+            .json()?;
+        let asset = assets
+            .into_iter()
+            .next()
+            .ok_or(Error::ResourceNotFound(format!(
+                "No Temurin {} {major} for {os} {arch}",
+                image.as_str()
+            )))?;
+        Ok(JavaPackage {
+            release_name: asset.release_name,
+            image,
+            ..asset.binary.package
+        })
+    }
+
+    /// Downloads and verifies a package, then extracts it into `root/<folder>`.
+    pub fn install(&self, package: &JavaPackage, root: &Path) -> Result<JavaRuntime, Error> {
+        let target = root.join(package.folder());
+        if target.exists() {
+            return Err(Error::ResourceNotFound(format!(
+                "{} is already installed",
+                package.folder()
+            )));
+        }
+        println!("☕ Downloading {}...", package.name);
+        let bytes = self
+            .client
+            .get(&package.link)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+        verify_hash(&bytes, &format!("sha256:{}", package.checksum))?;
+
+        // Extracted next to the target so a failed install never looks like a runtime
+        let staging = root.join(format!(".{}.part", package.folder()));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::create_dir_all(&staging)?;
+        let installed = unpack(&package.name, &bytes, &staging)
+            .and_then(|()| top_folder(&staging))
+            .and_then(|folder| Ok(std::fs::rename(folder, &target)?));
+        std::fs::remove_dir_all(&staging)?;
+        installed?;
+
+        from_homes(&runtime_homes(root))
+            .into_iter()
+            .find(|runtime| runtime.home.starts_with(&target))
+            .ok_or(Error::ResourceNotFound(format!(
+                "{} doesn't contain a Java runtime",
+                package.name
+            )))
+    }
+}
+
+fn unpack(name: &str, bytes: &[u8], target: &Path) -> Result<(), Error> {
+    let is_zip = Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if is_zip {
+        zip::ZipArchive::new(Cursor::new(bytes))?.extract(target)?;
+    } else {
+        tar::Archive::new(GzDecoder::new(bytes)).unpack(target)?;
+    }
+    Ok(())
+}
+
+// Temurin archives hold a single `jdk-21.0.1+12` style folder
+fn top_folder(dir: &Path) -> Result<PathBuf, Error> {
+    let mut folders = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+    match (folders.next(), folders.next()) {
+        (Some(folder), None) => Ok(folder),
+        _ => Err(Error::ResourceNotFound(
+            "Unexpected Java archive layout".to_string(),
+        )),
+    }
+}
+
+/// Runtimes installed by boxes in `root`, newest first.
+pub fn installed(root: &Path) -> Vec<JavaRuntime> {
+    from_homes(&runtime_homes(root))
+}
+
+/// Deletes an installed runtime, including the bundle folder around a macOS home.
+pub fn remove(root: &Path, runtime: &JavaRuntime) -> Result<(), Error> {
+    let folder = runtime
+        .home
+        .strip_prefix(root)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|first| root.join(first))
+        .ok_or(Error::ResourceNotFound(format!(
+            "{} wasn't installed by boxes",
+            runtime.home.display()
+        )))?;
+    std::fs::remove_dir_all(folder)?;
+    Ok(())
+}
@@ -0,0 +1,2 @@
+pub(crate) mod install;
+pub(crate) mod runtime;
@@ -0,0 +1,233 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::java::install::java_dir;
+use crate::minecraft::migration::Software;
+use crate::utils::compare_versions;
+use directories::BaseDirs;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// An installed JDK or JRE, read from the `release` file in its home folder.
+#[derive(Clone, Debug)]
+pub struct JavaRuntime {
+    pub home: PathBuf,
+    pub version: String,
+    pub major: u32,
+    pub vendor: Option<String>,
+}
+
+impl JavaRuntime {
+    pub fn from_home(home: &Path) -> Option<Self> {
+        let release = std::fs::read_to_string(home.join("release")).ok()?;
+        let value = |key: &str| {
+            release.lines().find_map(|line| {
+                let (k, v) = line.split_once('=')?;
+                (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+            })
+        };
+        let version = value("JAVA_VERSION")?;
+        let runtime = Self {
+            home: home.to_path_buf(),
+            major: major_version(&version)?,
+            version,
+            vendor: value("IMPLEMENTOR"),
+        };
+        runtime.executable().is_file().then_some(runtime)
+    }
+
+    pub fn executable(&self) -> PathBuf {
+        let name = if cfg!(windows) { "java.exe" } else { "java" };
+        self.home.join("bin").join(name)
+    }
+}
+
+// 1.8.0_392 is Java 8, 17.0.9 is Java 17
+pub fn major_version(version: &str) -> Option<u32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+/// Java versions a server can run on, from the oldest to the newest supported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct JavaRequirement {
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+impl JavaRequirement {
+    pub fn exactly(major: u32) -> Self {
+        Self {
+            min: major,
+            max: Some(major),
+        }
+    }
+
+    pub fn allows(self, major: u32) -> bool {
+        major >= self.min && self.max.is_none_or(|max| major <= max)
+    }
+}
+
+impl std::fmt::Display for JavaRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", self.min),
+            Some(max) => write!(f, "{} to {max}", self.min),
+            None => write!(f, "{}+", self.min),
+        }
+    }
+}
+
+/// Java a server needs, from its Minecraft (or proxy) version. `None` when the version is unknown.
+pub fn required_java(jar_name: &str, version: &str) -> Option<JavaRequirement> {
+    if version == "Unknown" {
+        return None;
+    }
+    if Software::of(jar_name) == Software::Proxy {
+        let min = if jar_name.eq_ignore_ascii_case("velocity") {
+            17
+        } else {
+            8
+        };
+        return Some(JavaRequirement { min, max: None });
+    }
+    let at_least = |v: &str| compare_versions(version, v) != Ordering::Less;
+    let requirement = if at_least("1.20.5") {
+        JavaRequirement { min: 21, max: None }
+    } else if at_least("1.18") {
+        JavaRequirement { min: 17, max: None }
+    } else if at_least("1.17") {
+        JavaRequirement { min: 16, max: None }
+    } else if at_least("1.13") {
+        JavaRequirement {
+            min: 8,
+            max: Some(16),
+        }
+    } else {
+        JavaRequirement::exactly(8)
+    };
+    Some(requirement)
+}
+
+// Folders holding one runtime per subfolder
+fn default_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = java_dir().into_iter().collect();
+    roots.extend([
+        PathBuf::from("/usr/lib/jvm"),
+        PathBuf::from("/usr/java"),
+        PathBuf::from("/opt/java"),
+        PathBuf::from("/Library/Java/JavaVirtualMachines"),
+    ]);
+    if let Some(sdkman) = std::env::var_os("SDKMAN_DIR") {
+        roots.push(PathBuf::from(sdkman).join("candidates/java"));
+    }
+    if let Some(dirs) = BaseDirs::new() {
+        roots.push(dirs.home_dir().join(".sdkman/candidates/java"));
+        roots.push(dirs.home_dir().join(".jdks"));
+    }
+    roots
+}
+
+// Home of the `java` found on PATH, following symlinks like /usr/bin/java
+fn path_home() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "java.exe" } else { "java" };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|java| java.is_file())?
+        .canonicalize()
+        .ok()?
+        .parent()?
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+/// Major version of the `java` on PATH, asked with `java -version` since discovery only
+/// knows runtimes with a `release` file.
+pub fn path_major() -> Option<u32> {
+    let output = Command::new("java").arg("-version").output().ok()?;
+    // `openjdk version "21.0.1" 2023-10-17`, printed to stderr
+    let banner = String::from_utf8_lossy(&output.stderr);
+    major_version(banner.split('"').nth(1)?)
+}
+
+/// Runtimes installed by boxes, in `JAVA_HOME`, on PATH, in the system JVM folders,
+/// SDKMAN and `~/.jdks`.
+pub fn discover() -> Vec<JavaRuntime> {
+    let mut homes: Vec<PathBuf> = std::env::var_os("JAVA_HOME")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(path_home())
+        .collect();
+    for root in default_roots() {
+        homes.extend(runtime_homes(&root));
+    }
+    from_homes(&homes)
+}
+
+// Subfolders of `root` that may be runtimes, including macOS bundles
+pub fn runtime_homes(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .map(|path| {
+            let bundle = path.join("Contents/Home");
+            if bundle.is_dir() {
+                bundle
+            } else {
+                path
+            }
+        })
+        .collect()
+}
+
+/// Runtimes among `homes`, once each even when reached through symlinks, newest first.
+pub fn from_homes(homes: &[PathBuf]) -> Vec<JavaRuntime> {
+    let mut seen = Vec::new();
+    let mut runtimes = Vec::new();
+    for home in homes {
+        let real = home.canonicalize().unwrap_or_else(|_| home.clone());
+        if seen.contains(&real) {
+            continue;
+        }
+        if let Some(runtime) = JavaRuntime::from_home(home) {
+            seen.push(real);
+            runtimes.push(runtime);
+        }
+    }
+    runtimes.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    runtimes
+}
+
+/// The runtime to use for `requirement`: the oldest allowed major version (the one the
+/// server was built for), in its newest release.
+pub fn select(runtimes: &[JavaRuntime], requirement: JavaRequirement) -> Option<&JavaRuntime> {
+    runtimes
+        .iter()
+        .filter(|r| requirement.allows(r.major))
+        .min_by(|a, b| {
+            a.major
+                .cmp(&b.major)
+                .then_with(|| compare_versions(&b.version, &a.version))
+        })
+}
+
+/// Finds a runtime for `requirement` among `runtimes`, with an error naming what's missing.
+pub fn find(runtimes: &[JavaRuntime], requirement: JavaRequirement) -> Result<PathBuf, Error> {
+    select(runtimes, requirement)
+        .map(JavaRuntime::executable)
+        .ok_or(Error::JavaNotFound(requirement.to_string()))
+}
@@ -6,10 +6,12 @@ use clap::Parser;
 mod cli;
 mod config;
 mod error;
+mod java;
 mod minecraft;
+#[cfg(test)]
+mod tests;
 mod themes;
 mod utils;
-mod tests;
 
 use cli::constructor;
 use cli::executor;
@@ -0,0 +1,738 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::console;
+use crate::minecraft::repository::Repository;
+use crate::minecraft::server::Server;
+use crate::utils::{
+    colorize, data_dir, format_size, hash_bytes, hash_file, hash_reader, parse_size, walk_files,
+    Color,
+};
+use chrono::{DateTime, Datelike, Local};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupFormat {
+    #[default]
+    TarZst,
+    Zip,
+    /// Deduplicated chunks in the shared repository of the backup directory
+    Incremental,
+}
+
+// Chunk repository of incremental backups, inside the backup directory
+const REPOSITORY_DIR: &str = "repository";
+
+// [backup] section of server_box.toml
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct BackupSettings {
+    pub directory: Option<PathBuf>,
+    #[serde(default)]
+    pub format: BackupFormat,
+    #[serde(default)]
+    pub worlds_only: bool,
+    pub keep_last: Option<usize>,
+    pub keep_daily: Option<usize>,
+    pub keep_weekly: Option<usize>,
+    pub max_total_size: Option<String>,
+}
+
+// Written next to each archive as <id>.toml
+#[derive(Deserialize, Serialize, Clone)]
+pub struct BackupManifest {
+    pub id: String,
+    pub server_name: String,
+    pub created: DateTime<Local>,
+    pub format: BackupFormat,
+    pub worlds_only: bool,
+    // Full size of the backup: the archive, or every file of an incremental backup.
+    // Retention (`max_total_size`) adds these up.
+    pub size: u64,
+    // Bytes this backup added to disk: the archive, or the chunks that were new to the
+    // repository. Missing from manifests written before it was recorded.
+    #[serde(default)]
+    pub stored: Option<u64>,
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+    // Chunks of the file in the repository, for incremental backups
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<String>,
+}
+
+impl BackupSettings {
+    pub fn has_retention(&self) -> bool {
+        self.keep_last.is_some()
+            || self.keep_daily.is_some()
+            || self.keep_weekly.is_some()
+            || self.max_total_size.is_some()
+    }
+
+    pub fn print(&self) {
+        let unset = || "Not set".to_string();
+        println!(
+            "  📦 Format: {}",
+            self.format.to_possible_value().unwrap().get_name()
+        );
+        println!("  📦 Worlds only: {}", self.worlds_only);
+        println!(
+            "  📦 Keep last: {}",
+            self.keep_last.map_or_else(unset, |n| n.to_string())
+        );
+        println!(
+            "  📦 Keep daily: {}",
+            self.keep_daily.map_or_else(unset, |n| n.to_string())
+        );
+        println!(
+            "  📦 Keep weekly: {}",
+            self.keep_weekly.map_or_else(unset, |n| n.to_string())
+        );
+        println!(
+            "  📦 Max total size: {}",
+            self.max_total_size.clone().unwrap_or_else(unset)
+        );
+    }
+}
+
+impl BackupManifest {
+    // The archive, or the repository holding the chunks of an incremental backup
+    pub fn archive(&self, dir: &Path) -> PathBuf {
+        match self.format {
+            BackupFormat::TarZst => dir.join(format!("{}.tar.zst", self.id)),
+            BackupFormat::Zip => dir.join(format!("{}.zip", self.id)),
+            BackupFormat::Incremental => dir.join(REPOSITORY_DIR),
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "  🗄️ {} ({}, {}{}, {} file(s){})",
+            colorize(&self.id, Color::Gold),
+            self.created.format("%Y-%m-%d %H:%M:%S"),
+            format_size(self.size),
+            match (self.format, self.stored) {
+                (BackupFormat::Incremental, Some(stored)) => {
+                    format!(" +{} stored", format_size(stored))
+                }
+                _ => String::new(),
+            },
+            self.files.len(),
+            if self.worlds_only {
+                ", worlds only"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+pub fn directory(server: &Server) -> Result<PathBuf, Error> {
+    if let Some(directory) = &server.backup.directory {
+        return Ok(directory.clone());
+    }
+    let backups = data_dir()?.join("backups");
+    let dir = backups.join(safe_name(&server.server_name));
+    // Folders named before names were escaped ("my server" became "my_server")
+    let legacy = backups.join(legacy_safe_name(&server.server_name));
+    if !dir.exists() && legacy.is_dir() {
+        return Ok(legacy);
+    }
+    Ok(dir)
+}
+
+// Keeps ASCII letters, digits and dashes, and escapes every other byte as `_xx`, so two
+// names never share a folder
+fn safe_name(name: &str) -> String {
+    name.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b == b'-' {
+                char::from(b).to_string()
+            } else {
+                format!("_{b:02x}")
+            }
+        })
+        .collect()
+}
+
+fn legacy_safe_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Top level folders with a level.dat (world, world_nether, world_the_end...)
+pub fn world_dirs(server: &Server) -> Result<Vec<String>, Error> {
+    let mut worlds = Vec::new();
+    for entry in std::fs::read_dir(&server.location)? {
+        let path = entry?.path();
+        if path.join("level.dat").is_file() {
+            worlds.push(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+    }
+    worlds.sort();
+    Ok(worlds)
+}
+
+// Files to archive, relative to the server folder
+fn backup_files(
+    server: &Server,
+    worlds_only: bool,
+    backup_dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    if !worlds_only {
+        let exclude = [
+            backup_dir.to_path_buf(),
+            server.location.join(console::BRIDGE_FILE),
+        ];
+        return walk_files(&server.location, &exclude);
+    }
+    let mut files = Vec::new();
+    for world in world_dirs(server)? {
+        let world = PathBuf::from(world);
+        let world_files = walk_files(&server.location.join(&world), &[backup_dir.to_path_buf()])?;
+        files.extend(world_files.into_iter().map(|f| world.join(f)));
+    }
+    if files.is_empty() {
+        return Err(Error::ResourceNotFound(
+            "No world folders (with a level.dat) found".to_string(),
+        ));
+    }
+    Ok(files)
+}
+
+/// Archives the server (or only its worlds) into its backup directory.
+pub fn create(
+    server: &Server,
+    format: BackupFormat,
+    worlds_only: bool,
+) -> Result<BackupManifest, Error> {
+    let dir = directory(server)?;
+    std::fs::create_dir_all(&dir)?;
+    let created = Local::now();
+    let base_id = format!(
+        "{}-{}",
+        safe_name(&server.server_name),
+        created.format("%Y%m%d-%H%M%S")
+    );
+    let mut id = base_id.clone();
+    let mut attempt = 1;
+    while dir.join(format!("{id}.toml")).exists() {
+        attempt += 1;
+        id = format!("{base_id}-{attempt}");
+    }
+
+    let files = backup_files(server, worlds_only, &dir)?;
+    println!(
+        "🗄️ Backing up {} file(s) from {}...",
+        files.len(),
+        colorize(&server.server_name, Color::Gold)
+    );
+    let mut manifest = BackupManifest {
+        id,
+        server_name: server.server_name.clone(),
+        created,
+        format,
+        worlds_only,
+        size: 0,
+        stored: None,
+        files: Vec::new(),
+    };
+    for file in &files {
+        let path = server.location.join(file);
+        manifest.files.push(BackupFile {
+            path: archive_path(file),
+            size: path.metadata()?.len(),
+            hash: hash_file("sha256", &path)?,
+            chunks: Vec::new(),
+        });
+    }
+
+    let archive = manifest.archive(&dir);
+    if format == BackupFormat::Incremental {
+        // Only the chunks that weren't in the repository yet are stored again
+        let repository = Repository::open(&archive)?;
+        let mut stored = 0;
+        for (file, entry) in files.iter().zip(manifest.files.iter_mut()) {
+            let (chunks, added) = repository.store_file(&server.location.join(file))?;
+            entry.chunks = chunks;
+            manifest.size += entry.size;
+            stored += added;
+        }
+        manifest.stored = Some(stored);
+    } else {
+        let temp = archive.with_extension("part");
+        let written = match format {
+            BackupFormat::Zip => write_zip(&server.location, &files, &temp),
+            _ => write_tar_zst(&server.location, &files, &temp),
+        };
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e);
+        }
+        std::fs::rename(&temp, &archive)?;
+        manifest.size = archive.metadata()?.len();
+        manifest.stored = Some(manifest.size);
+    }
+    std::fs::write(
+        dir.join(format!("{}.toml", manifest.id)),
+        toml::to_string(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// Like `create`, but a running server first stops saving and flushes its worlds,
+/// so no region file is archived halfway through a write.
+pub fn create_consistent(
+    server: &Server,
+    format: BackupFormat,
+    worlds_only: bool,
+) -> Result<BackupManifest, Error> {
+    if !server.is_running() {
+        return create(server, format, worlds_only);
+    }
+    console::with_saving_paused(server, console::SAVE_TIMEOUT, || {
+        create(server, format, worlds_only)
+    })
+}
+
+// Archive entries always use forward slashes
+fn archive_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+fn write_zip(root: &Path, files: &[PathBuf], archive: &Path) -> Result<(), Error> {
+    let mut zip = zip::ZipWriter::new(File::create(archive)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    for file in files {
+        zip.start_file(archive_path(file), options)?;
+        std::io::copy(&mut File::open(root.join(file))?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn write_tar_zst(root: &Path, files: &[PathBuf], archive: &Path) -> Result<(), Error> {
+    let encoder = zstd::Encoder::new(File::create(archive)?, 3)?;
+    let mut tar = tar::Builder::new(encoder);
+    for file in files {
+        tar.append_path_with_name(root.join(file), archive_path(file))?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Every backup of the server, newest first. Other servers sharing the backup directory
+/// are left out.
+pub fn list(server: &Server) -> Result<Vec<BackupManifest>, Error> {
+    Ok(manifests(&directory(server)?)?
+        .into_iter()
+        .filter(|b| b.server_name == server.server_name)
+        .collect())
+}
+
+// Every backup in a backup directory, whichever server it belongs to, newest first
+fn manifests(dir: &Path) -> Result<Vec<BackupManifest>, Error> {
+    let mut backups = Vec::new();
+    if !dir.is_dir() {
+        return Ok(backups);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().unwrap_or_default() != "toml" {
+            continue;
+        }
+        let Ok(mut manifest) = toml::from_str::<BackupManifest>(&std::fs::read_to_string(&path)?)
+        else {
+            continue;
+        };
+        // Older incremental manifests recorded the new chunks as their size
+        if manifest.stored.is_none() {
+            manifest.stored = Some(manifest.size);
+            if manifest.format == BackupFormat::Incremental {
+                manifest.size = manifest.files.iter().map(|f| f.size).sum();
+            }
+        }
+        if manifest.archive(dir).exists() {
+            backups.push(manifest);
+        }
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created));
+    Ok(backups)
+}
+
+/// Gives the server's backups a new owner name, so `list` keeps finding them after a rename.
+pub fn reassign(server: &Server, name: &str) -> Result<(), Error> {
+    let dir = directory(server)?;
+    for mut backup in list(server)? {
+        backup.server_name = name.to_string();
+        std::fs::write(
+            dir.join(format!("{}.toml", backup.id)),
+            toml::to_string(&backup)?,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn find(server: &Server, id: &str) -> Result<BackupManifest, Error> {
+    list(server)?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or(Error::ResourceNotFound(format!("Backup {id} not found")))
+}
+
+/// Picks the backups (given newest first) that the retention rules don't keep.
+/// Without any rule every backup is kept. The newest backup is never pruned.
+/// `max_total_size` adds up each backup's full `size`, so incremental backups count
+/// every file even though they share chunks.
+pub fn select_prunable<'a>(
+    backups: &'a [BackupManifest],
+    settings: &BackupSettings,
+) -> Result<Vec<&'a BackupManifest>, Error> {
+    if !settings.has_retention() || backups.is_empty() {
+        return Ok(Vec::new());
+    }
+    let count_rules = settings.keep_last.is_some()
+        || settings.keep_daily.is_some()
+        || settings.keep_weekly.is_some();
+    let mut keep: HashSet<&str> = HashSet::new();
+    if count_rules {
+        for backup in backups.iter().take(settings.keep_last.unwrap_or(0)) {
+            keep.insert(&backup.id);
+        }
+        let mut days = Vec::new();
+        let mut weeks = Vec::new();
+        for backup in backups {
+            let day = backup.created.date_naive();
+            if days.len() < settings.keep_daily.unwrap_or(0) && !days.contains(&day) {
+                days.push(day);
+                keep.insert(&backup.id);
+            }
+            let week = (
+                backup.created.iso_week().year(),
+                backup.created.iso_week().week(),
+            );
+            if weeks.len() < settings.keep_weekly.unwrap_or(0) && !weeks.contains(&week) {
+                weeks.push(week);
+                keep.insert(&backup.id);
+            }
+        }
+    } else {
+        keep.extend(backups.iter().map(|b| b.id.as_str()));
+    }
+    keep.insert(&backups[0].id);
+
+    if let Some(max) = &settings.max_total_size {
+        let max = parse_size(max)?;
+        let mut total = 0;
+        for (index, backup) in backups.iter().enumerate() {
+            if !keep.contains(backup.id.as_str()) {
+                continue;
+            }
+            total += backup.size;
+            if total > max && index > 0 {
+                keep.remove(backup.id.as_str());
+            }
+        }
+    }
+    Ok(backups
+        .iter()
+        .filter(|b| !keep.contains(b.id.as_str()))
+        .collect())
+}
+
+/// Deletes the backups the server's retention rules don't keep.
+/// Returns them with the bytes freed on disk.
+pub fn prune(server: &Server, dry_run: bool) -> Result<(Vec<BackupManifest>, u64), Error> {
+    let dir = directory(server)?;
+    let backups = list(server)?;
+    let prunable: Vec<BackupManifest> = select_prunable(&backups, &server.backup)?
+        .into_iter()
+        .cloned()
+        .collect();
+    let mut freed = 0;
+    for backup in &prunable {
+        if backup.format != BackupFormat::Incremental {
+            let archive = backup.archive(&dir);
+            freed += archive.metadata().map_or(0, |m| m.len());
+            if !dry_run {
+                std::fs::remove_file(archive)?;
+            }
+        }
+        if !dry_run {
+            std::fs::remove_file(dir.join(format!("{}.toml", backup.id)))?;
+        }
+    }
+    if prunable
+        .iter()
+        .any(|b| b.format == BackupFormat::Incremental)
+    {
+        // Drop the chunks no remaining backup refers to, including the backups of other
+        // servers sharing the repository
+        let kept: Vec<BackupManifest> = manifests(&dir)?
+            .into_iter()
+            .filter(|b| !prunable.iter().any(|p| p.id == b.id))
+            .collect();
+        let referenced: HashSet<&str> = kept
+            .iter()
+            .flat_map(|b| &b.files)
+            .flat_map(|f| &f.chunks)
+            .map(String::as_str)
+            .collect();
+        let repository = Repository::open(&dir.join(REPOSITORY_DIR))?;
+        freed += if dry_run {
+            repository.garbage_size(&referenced)?
+        } else {
+            repository.collect_garbage(&referenced)?
+        };
+    }
+    Ok((prunable, freed))
+}
+
+/// Writes the files of a backup to `target`, verifying each of them against the manifest.
+pub fn extract(server: &Server, backup: &BackupManifest, target: &Path) -> Result<(), Error> {
+    let archive = backup.archive(&directory(server)?);
+    std::fs::create_dir_all(target)?;
+    match backup.format {
+        BackupFormat::TarZst => {
+            tar::Archive::new(zstd::Decoder::new(File::open(&archive)?)?).unpack(target)?;
+        }
+        BackupFormat::Zip => zip::ZipArchive::new(File::open(&archive)?)?.extract(target)?,
+        BackupFormat::Incremental => {
+            let repository = Repository::open(&archive)?;
+            for file in &backup.files {
+                let path = target.join(&file.path);
+                std::fs::create_dir_all(path.parent().unwrap_or(target))?;
+                let mut output = File::create(&path)?;
+                for chunk in &file.chunks {
+                    output.write_all(&repository.load(chunk)?)?;
+                }
+            }
+        }
+    }
+    for file in &backup.files {
+        let actual = hash_file("sha256", &target.join(&file.path))?;
+        if actual != file.hash {
+            return Err(Error::HashMismatch {
+                expected: format!("{} {}", file.path, file.hash),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reads a backup back and compares every file with the manifest.
+/// Returns a description of each problem found.
+pub fn check(server: &Server, backup: &BackupManifest) -> Result<Vec<String>, Error> {
+    let archive = backup.archive(&directory(server)?);
+    let mut problems = Vec::new();
+    let mut expected: HashMap<&str, &str> = backup
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.hash.as_str()))
+        .collect();
+    let mut compare =
+        |path: &str, actual: Result<String, Error>| match (expected.remove(path), actual) {
+            (Some(hash), Ok(actual)) if hash == actual => {}
+            (Some(_), Ok(_)) => problems.push(format!("{path} doesn't match its hash")),
+            (_, Err(e)) => problems.push(format!("{path} can't be read: {e}")),
+            (None, Ok(_)) => problems.push(format!("{path} is not in the manifest")),
+        };
+    match backup.format {
+        BackupFormat::TarZst => {
+            let mut tar = tar::Archive::new(zstd::Decoder::new(File::open(&archive)?)?);
+            for entry in tar.entries()? {
+                let entry = entry?;
+                if entry.header().entry_type().is_file() {
+                    let path = entry.path()?.to_string_lossy().to_string();
+                    compare(&path, hash_reader("sha256", entry));
+                }
+            }
+        }
+        BackupFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(File::open(&archive)?)?;
+            for index in 0..zip.len() {
+                let entry = zip.by_index(index)?;
+                if entry.is_file() {
+                    let path = entry.name().to_string();
+                    compare(&path, hash_reader("sha256", entry));
+                }
+            }
+        }
+        BackupFormat::Incremental => {
+            let repository = Repository::open(&archive)?;
+            for file in &backup.files {
+                let mut content = Vec::new();
+                let mut damaged = None;
+                for chunk in &file.chunks {
+                    match repository.load(chunk) {
+                        Ok(bytes) => content.extend_from_slice(&bytes),
+                        Err(e) => {
+                            damaged.get_or_insert(e);
+                        }
+                    }
+                }
+                let actual = match damaged {
+                    Some(e) => Err(e),
+                    None => hash_bytes("sha256", &content),
+                };
+                compare(&file.path, actual);
+            }
+        }
+    }
+    for path in expected.keys() {
+        problems.push(format!("{path} is missing"));
+    }
+    problems.sort();
+    Ok(problems)
+}
+
+// Sibling of the server folder, so renaming it into place stays on the same filesystem
+fn sibling(server: &Server, label: &str) -> PathBuf {
+    let name = server
+        .location
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    server
+        .location
+        .with_file_name(format!(".{name}.{label}-{stamp}"))
+}
+
+/// Replaces the server (or only its worlds) with a backup. A safety backup of the current
+/// state is taken first and returned. The backup is extracted and verified in a temporary
+/// folder next to the server, which is then swapped in with renames.
+pub fn restore(
+    server: &Server,
+    backup: &BackupManifest,
+    worlds_only: bool,
+) -> Result<(BackupManifest, Server), Error> {
+    if server.is_running() {
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    let worlds_only = worlds_only || backup.worlds_only;
+    if !worlds_only && directory(server)?.starts_with(&server.location) {
+        return Err(Error::ResourceNotFound(
+            "The backup directory is inside the server folder, restore with --worlds-only"
+                .to_string(),
+        ));
+    }
+    println!("🗄️ Taking a safety backup first...");
+    let safety = create(server, server.backup.format, worlds_only)?;
+
+    let staging = sibling(server, "restore");
+    if let Err(e) = extract(server, backup, &staging) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    let swapped = if worlds_only {
+        swap_worlds(server, &staging)
+    } else {
+        swap_server(server, &staging)
+    };
+    let _ = std::fs::remove_dir_all(&staging);
+    swapped?;
+
+    let restored = restored_info(server, worlds_only)?;
+    restored.write();
+    Ok((safety, restored))
+}
+
+fn swap_server(server: &Server, staging: &Path) -> Result<(), Error> {
+    let old = sibling(server, "old");
+    std::fs::rename(&server.location, &old)?;
+    if let Err(e) = std::fs::rename(staging, &server.location) {
+        std::fs::rename(&old, &server.location)?;
+        return Err(e.into());
+    }
+    std::fs::remove_dir_all(&old)?;
+    Ok(())
+}
+
+fn swap_worlds(server: &Server, staging: &Path) -> Result<(), Error> {
+    let staged = Server {
+        location: staging.to_path_buf(),
+        ..server.clone()
+    };
+    let worlds = world_dirs(&staged)?;
+    if worlds.is_empty() {
+        return Err(Error::ResourceNotFound(
+            "The backup has no world folders".to_string(),
+        ));
+    }
+    let old = sibling(server, "old");
+    std::fs::create_dir_all(&old)?;
+    let mut moved: Vec<&String> = Vec::new();
+    for world in &worlds {
+        let current = server.location.join(world);
+        let result = if current.exists() {
+            std::fs::rename(&current, old.join(world))
+        } else {
+            Ok(())
+        }
+        .and_then(|()| std::fs::rename(staging.join(world), &current));
+        if let Err(e) = result {
+            // Put back every world swapped so far, including this one
+            for world in moved.iter().copied().chain([world]) {
+                let current = server.location.join(world);
+                if old.join(world).exists() {
+                    let _ = std::fs::remove_dir_all(&current);
+                    std::fs::rename(old.join(world), &current)?;
+                }
+            }
+            let _ = std::fs::remove_dir_all(&old);
+            return Err(e.into());
+        }
+        moved.push(world);
+    }
+    std::fs::remove_dir_all(&old)?;
+    Ok(())
+}
+
+// server_box.toml after a restore: the restored settings, at the server's current place
+fn restored_info(server: &Server, worlds_only: bool) -> Result<Server, Error> {
+    let path = server.location.join("server_box.toml");
+    if worlds_only || !path.exists() {
+        return Ok(server.clone());
+    }
+    let mut restored: Server = toml::from_str(&std::fs::read_to_string(path)?)?;
+    restored.server_name.clone_from(&server.server_name);
+    restored.location.clone_from(&server.location);
+    restored.backup = server.backup.clone();
+    Ok(restored)
+}
@@ -0,0 +1,340 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::ChildStdin;
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Written by `Server::run` with "<port> <token>" while the server is up
+pub const BRIDGE_FILE: &str = ".boxes-console";
+
+const RCON_AUTH: i32 = 3;
+const RCON_COMMAND: i32 = 2;
+const TIMEOUT: Duration = Duration::from_secs(10);
+// How long a running server gets to flush its worlds before they are copied
+pub const SAVE_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+// Stdin of the server started by this process
+static SERVER_STDIN: Mutex<Option<ChildStdin>> = Mutex::new(None);
+static FORWARD_STDIN: Once = Once::new();
+
+/// A way to send commands to a running server: RCON when it is enabled in
+/// server.properties, otherwise the console of the boxes process that started it.
+pub enum Console {
+    Rcon { stream: TcpStream, next_id: i32 },
+    Bridge { port: u16, token: String },
+}
+
+impl Console {
+    pub fn connect(server: &Server) -> Result<Self, Error> {
+        let manipulator = ServerManipulator {
+            server: server.clone(),
+        };
+        let properties = if server.location.join("server.properties").exists() {
+            manipulator.get_server_properties().unwrap_or_default()
+        } else {
+            HashMap::default()
+        };
+        let password = properties.get("rcon.password").cloned().unwrap_or_default();
+        if properties.get("enable-rcon").map(String::as_str) == Some("true") && !password.is_empty()
+        {
+            let port = properties
+                .get("rcon.port")
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(25575);
+            return Self::rcon(port, &password);
+        }
+        let bridge = std::fs::read_to_string(server.location.join(BRIDGE_FILE)).map_err(|_| {
+            Error::Console(
+                "Enable RCON in server.properties or start the server with boxes".to_string(),
+            )
+        })?;
+        let (port, token) = bridge
+            .trim()
+            .split_once(' ')
+            .ok_or(Error::Console(format!("Invalid {BRIDGE_FILE}")))?;
+        Ok(Console::Bridge {
+            port: port
+                .parse()
+                .map_err(|_| Error::Console(format!("Invalid {BRIDGE_FILE}")))?,
+            token: token.to_string(),
+        })
+    }
+
+    pub fn rcon(port: u16, password: &str) -> Result<Self, Error> {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        write_packet(&mut stream, 1, RCON_AUTH, password)?;
+        if read_packet(&mut stream)?.0 == -1 {
+            return Err(Error::Console("RCON password was rejected".to_string()));
+        }
+        Ok(Console::Rcon { stream, next_id: 1 })
+    }
+
+    /// Sends a command, returning its response (always empty through the bridge).
+    pub fn send(&mut self, command: &str) -> Result<String, Error> {
+        match self {
+            Console::Rcon { stream, next_id } => {
+                *next_id += 1;
+                write_packet(stream, *next_id, RCON_COMMAND, command)?;
+                let (id, body) = read_packet(stream)?;
+                if id != *next_id {
+                    return Err(Error::Console(format!(
+                        "Unexpected RCON response to {command}"
+                    )));
+                }
+                Ok(body)
+            }
+            Console::Bridge { port, token } => {
+                let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, *port))?;
+                stream.set_read_timeout(Some(TIMEOUT))?;
+                writeln!(stream, "{token}\n{command}")?;
+                let mut reply = String::new();
+                BufReader::new(stream).read_line(&mut reply)?;
+                if reply.trim() != "ok" {
+                    return Err(Error::Console(format!("Console refused {command}")));
+                }
+                Ok(String::new())
+            }
+        }
+    }
+}
+
+fn write_packet(stream: &mut TcpStream, id: i32, kind: i32, body: &str) -> Result<(), Error> {
+    let length = i32::try_from(body.len() + 10)
+        .map_err(|_| Error::Console("RCON command is too long".to_string()))?;
+    let mut packet = Vec::with_capacity(body.len() + 14);
+    packet.extend_from_slice(&length.to_le_bytes());
+    packet.extend_from_slice(&id.to_le_bytes());
+    packet.extend_from_slice(&kind.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    stream.write_all(&packet)?;
+    Ok(())
+}
+
+fn read_packet(stream: &mut TcpStream) -> Result<(i32, String), Error> {
+    let mut int = [0; 4];
+    stream.read_exact(&mut int)?;
+    let length = usize::try_from(i32::from_le_bytes(int)).unwrap_or(0);
+    if !(10..=4096 + 10).contains(&length) {
+        return Err(Error::Console("Invalid RCON packet".to_string()));
+    }
+    let mut packet = vec![0; length];
+    stream.read_exact(&mut packet)?;
+    let id = i32::from_le_bytes([packet[0], packet[1], packet[2], packet[3]]);
+    let body = String::from_utf8_lossy(&packet[8..length - 2]).to_string();
+    Ok((id, body))
+}
+
+/// Hands the server's stdin to the console bridge: commands from other boxes processes
+/// (authenticated with a token) are forwarded to it. Returns the path of the bridge file,
+/// which should be removed once the server stops.
+pub fn open_bridge(location: &Path, stdin: ChildStdin) -> Result<PathBuf, Error> {
+    *SERVER_STDIN
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(stdin);
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| Error::Console(format!("No randomness for the bridge token: {e}")))?;
+    let token = bytes.iter().fold(String::new(), |mut token, b| {
+        let _ = write!(token, "{b:02x}");
+        token
+    });
+
+    // Only the owner may read the token, so other users can't type into the console
+    let path = location.join(BRIDGE_FILE);
+    let _ = std::fs::remove_file(&path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    write!(options.open(&path)?, "{port} {token}")?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let _ = handle_bridge_client(stream, &token);
+        }
+    });
+    Ok(path)
+}
+
+/// Forwards the lines typed in this terminal to the server. The thread owns stdin from
+/// then on, so prompts have to come before.
+pub fn forward_stdin() {
+    FORWARD_STDIN.call_once(|| {
+        thread::spawn(|| {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                let _ = forward(&line);
+            }
+        });
+    });
+}
+
+/// Whether a boxes process is serving the console bridge of the server in `location`.
+/// A bridge file left behind by a crash points at a port nothing listens on anymore.
+pub fn bridge_is_open(location: &Path) -> bool {
+    let Ok(bridge) = std::fs::read_to_string(location.join(BRIDGE_FILE)) else {
+        return false;
+    };
+    bridge
+        .split_whitespace()
+        .next()
+        .and_then(|port| port.parse::<u16>().ok())
+        .is_some_and(|port| TcpStream::connect((Ipv4Addr::LOCALHOST, port)).is_ok())
+}
+
+fn handle_bridge_client(stream: TcpStream, token: &str) -> Result<(), Error> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut stream = stream;
+    if line.trim() != token {
+        writeln!(stream, "denied")?;
+        return Ok(());
+    }
+    line.clear();
+    reader.read_line(&mut line)?;
+    match forward(line.trim()) {
+        Ok(()) => writeln!(stream, "ok")?,
+        Err(_) => writeln!(stream, "error")?,
+    }
+    Ok(())
+}
+
+fn forward(command: &str) -> Result<(), Error> {
+    let mut stdin = SERVER_STDIN
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let stdin = stdin
+        .as_mut()
+        .ok_or(Error::Console("Server is not running".to_string()))?;
+    writeln!(stdin, "{command}")?;
+    stdin.flush()?;
+    Ok(())
+}
+
+pub fn log_file(server: &Server) -> PathBuf {
+    server.location.join("logs").join("latest.log")
+}
+
+/// Waits until a line containing `needle` is appended to the log after `offset`.
+pub fn wait_for_log(log: &Path, offset: u64, needle: &str, timeout: Duration) -> Result<(), Error> {
+    wait_for_log_while(log, offset, needle, timeout, || Ok(()))
+}
+
+/// Like `wait_for_log`, but gives up as soon as `check` fails, e.g. because the process
+/// writing the log exited.
+pub fn wait_for_log_while(
+    log: &Path,
+    offset: u64,
+    needle: &str,
+    timeout: Duration,
+    mut check: impl FnMut() -> Result<(), Error>,
+) -> Result<(), Error> {
+    let started = Instant::now();
+    let mut offset = offset;
+    let mut pending = String::new();
+    loop {
+        if let Ok(mut file) = std::fs::File::open(log) {
+            // The log was rotated, start over
+            if file.metadata()?.len() < offset {
+                offset = 0;
+            }
+            file.seek(SeekFrom::Start(offset))?;
+            let mut appended = Vec::new();
+            file.read_to_end(&mut appended)?;
+            offset += appended.len() as u64;
+            pending.push_str(&String::from_utf8_lossy(&appended));
+            if pending.contains(needle) {
+                return Ok(());
+            }
+            if let Some(last_line) = pending.rfind('\n') {
+                pending.drain(..=last_line);
+            }
+        }
+        check()?;
+        if started.elapsed() > timeout {
+            return Err(Error::Console(format!(
+                "Timed out waiting for \"{needle}\" in {}",
+                log.display()
+            )));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// Saves all worlds and waits until they are on disk. RCON answers once that is done, the
+// bridge doesn't answer at all so the log has to tell.
+fn flush(console: &mut Console, log: &Path, timeout: Duration) -> Result<(), Error> {
+    if let Console::Rcon { stream, .. } = console {
+        stream.set_read_timeout(Some(timeout))?;
+        let response = console.send("save-all flush");
+        if let Console::Rcon { stream, .. } = console {
+            stream.set_read_timeout(Some(TIMEOUT))?;
+        }
+        let response = response?;
+        if !response.contains("Saved the game") {
+            return Err(Error::Console(format!(
+                "save-all flush failed: {}",
+                response.trim()
+            )));
+        }
+        return Ok(());
+    }
+    let offset = log.metadata().map_or(0, |m| m.len());
+    console.send("save-all flush")?;
+    wait_for_log(log, offset, "Saved the game", timeout)
+}
+
+/// Runs `action` with world saving turned off and everything flushed to disk, so files
+/// can be copied without the server writing to them. Saving is always turned back on.
+pub fn with_saving_paused<T>(
+    server: &Server,
+    timeout: Duration,
+    action: impl FnOnce() -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut console = Console::connect(server)?;
+
+    println!("💾 Pausing saving on {}...", server.server_name);
+    let result = console
+        .send("save-off")
+        .and_then(|_| flush(&mut console, &log_file(server), timeout))
+        .and_then(|()| action());
+
+    // The connection may have dropped, so reconnect once before giving up
+    let resumed = console
+        .send("save-on")
+        .or_else(|_| Console::connect(server)?.send("save-on"));
+    match &resumed {
+        Ok(_) => println!("💾 Resumed saving on {}", server.server_name),
+        Err(e) => println!(
+            "🚨 Could not turn saving back on, run save-on on {} manually: {e}",
+            server.server_name
+        ),
+    }
+    let value = result?;
+    resumed?;
+    Ok(value)
+}
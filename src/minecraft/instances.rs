@@ -0,0 +1,200 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::backup::{self, world_dirs};
+use crate::minecraft::console;
+use crate::minecraft::plugin_lock::PluginLock;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::utils::{canonize, is_within, link_or_copy, move_dir, walk_files, CopyMethod};
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+
+// Ports a server can listen on: property, the property enabling it and its default
+const PORT_PROPERTIES: [(&str, Option<&str>, u16); 3] = [
+    ("server-port", None, 25565),
+    ("rcon.port", Some("enable-rcon"), 25575),
+    ("query.port", Some("enable-query"), 25565),
+];
+
+fn is_enabled(manipulator: &ServerManipulator, enabled_by: Option<&str>) -> bool {
+    enabled_by.is_none_or(|key| manipulator.get_property(key).as_deref() == Some("true"))
+}
+
+#[derive(Default)]
+pub struct CloneOptions {
+    pub without_worlds: bool,
+    pub without_plugins: bool,
+}
+
+#[derive(Default)]
+pub struct CopyReport {
+    pub reflinked: usize,
+    pub hardlinked: usize,
+    pub copied: usize,
+}
+
+impl CopyReport {
+    fn add(&mut self, method: CopyMethod) {
+        match method {
+            CopyMethod::Reflink => self.reflinked += 1,
+            CopyMethod::Hardlink => self.hardlinked += 1,
+            CopyMethod::Copy => self.copied += 1,
+        }
+    }
+}
+
+/// Ports the server is set to listen on, including a proxy's `bind` in velocity.toml.
+pub fn ports(server: &Server) -> Vec<u16> {
+    let manipulator = ServerManipulator {
+        server: server.clone(),
+    };
+    PORT_PROPERTIES
+        .iter()
+        .filter(|(_, enabled_by, _)| is_enabled(&manipulator, *enabled_by))
+        .filter_map(|(key, _, _)| manipulator.get_property(key)?.parse().ok())
+        .chain(proxy_port(&server.location))
+        .collect()
+}
+
+// Port of `bind = "host:port"` in velocity.toml, proxies have no server.properties
+fn proxy_port(location: &Path) -> Option<u16> {
+    let text = std::fs::read_to_string(location.join("velocity.toml")).ok()?;
+    let velocity: toml::Table = toml::from_str(&text).ok()?;
+    let (_, port) = velocity.get("bind")?.as_str()?.rsplit_once(':')?;
+    port.parse().ok()
+}
+
+/// First port from `start` that no other server uses and nothing is listening on.
+pub fn free_port(start: u16, taken: &[u16]) -> Result<u16, Error> {
+    (start..=u16::MAX)
+        .find(|port| {
+            !taken.contains(port) && TcpListener::bind((Ipv4Addr::UNSPECIFIED, *port)).is_ok()
+        })
+        .ok_or(Error::ResourceNotFound("No free port found".to_string()))
+}
+
+/// Copies a server into `location` under a new name, giving it ports that don't clash with
+/// `taken`. Jars are hard linked when reflinks aren't supported; other files are copied.
+pub fn clone_server(
+    source: &Server,
+    name: &str,
+    location: &Path,
+    options: &CloneOptions,
+    taken: &[u16],
+) -> Result<(Server, CopyReport), Error> {
+    if location.exists() && location.read_dir()?.next().is_some() {
+        return Err(Error::InvalidInput(format!(
+            "{} is not empty",
+            location.display()
+        )));
+    }
+    std::fs::create_dir_all(location)?;
+
+    let mut exclude = vec![source.location.join(console::BRIDGE_FILE)];
+    if options.without_worlds {
+        exclude.extend(world_dirs(source)?.iter().map(|w| source.location.join(w)));
+    }
+    if options.without_plugins {
+        exclude.push(source.location.join("plugins"));
+        exclude.push(PluginLock::path(source));
+    }
+    let copy = || copy_files(&source.location, location, &exclude);
+    let report = if source.is_running() && !options.without_worlds {
+        console::with_saving_paused(source, console::SAVE_TIMEOUT, copy)?
+    } else {
+        copy()?
+    };
+
+    let mut clone = source.clone();
+    clone.server_name = name.to_string();
+    clone.location = canonize(location)?;
+    // The clone's backups go to its own default directory, never next to the source's
+    clone.backup.directory = None;
+    clone.write();
+
+    let manipulator = ServerManipulator {
+        server: clone.clone(),
+    };
+    let mut taken = taken.to_vec();
+    for (key, enabled_by, default) in PORT_PROPERTIES {
+        if !is_enabled(&manipulator, enabled_by) {
+            continue;
+        }
+        let current = manipulator
+            .get_property(key)
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(default);
+        let port = free_port(current, &taken)?;
+        manipulator.set_property(key, &port.to_string())?;
+        taken.push(port);
+    }
+    Ok((clone, report))
+}
+
+fn copy_files(from: &Path, to: &Path, exclude: &[PathBuf]) -> Result<CopyReport, Error> {
+    let mut report = CopyReport::default();
+    for file in walk_files(from, exclude)? {
+        let target = to.join(&file);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let is_jar = file.extension().unwrap_or_default() == "jar";
+        report.add(link_or_copy(&from.join(&file), &target, is_jar)?);
+    }
+    Ok(report)
+}
+
+/// Gives a stopped server a new name. Backups in the default backup directory follow it.
+pub fn rename(server: &Server, name: &str) -> Result<Server, Error> {
+    if server.is_running() {
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    let old_backups = backup::directory(server)?;
+    let mut renamed = server.clone();
+    renamed.server_name = name.to_string();
+    let new_backups = backup::directory(&renamed)?;
+    backup::reassign(server, name)?;
+    if old_backups != new_backups && old_backups.is_dir() && !new_backups.exists() {
+        move_dir(&old_backups, &new_backups)?;
+    }
+    renamed.write();
+    Ok(renamed)
+}
+
+/// Moves a stopped server to `location`, copying it when it's on another filesystem.
+pub fn relocate(server: &Server, location: &Path) -> Result<Server, Error> {
+    if server.is_running() {
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    if is_within(location, &server.location) {
+        return Err(Error::InvalidInput(format!(
+            "Can't move {} into its own folder",
+            server.server_name
+        )));
+    }
+    if location.exists() {
+        if location.read_dir()?.next().is_some() {
+            return Err(Error::InvalidInput(format!(
+                "{} is not empty",
+                location.display()
+            )));
+        }
+        std::fs::remove_dir(location)?;
+    }
+    if let Some(parent) = location.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    move_dir(&server.location, location)?;
+    let mut moved = server.clone();
+    moved.location = canonize(location)?;
+    moved.write();
+    Ok(moved)
+}
@@ -5,13 +5,17 @@ use crate::get_exec_time;
 use crate::minecraft::server::Server;
 use crate::utils::colorize;
 use crate::utils::{download, Color};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const JARS_TOML: &str = include_str!("../../jars.toml");
 
+fn short_hash(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
 pub fn load() -> Result<JarManager, Error> {
     let jars: JarManager = toml::from_str(JARS_TOML)?;
 
@@ -81,6 +85,43 @@ pub struct PurpurBuildsInfo {
     pub all: Vec<String>,
 }
 
+// Builds of a version with their changes, from PaperMC's build API
+#[derive(Deserialize)]
+pub struct PaperBuildsInfo {
+    pub builds: Vec<PaperBuild>,
+}
+
+#[derive(Deserialize)]
+pub struct PaperBuild {
+    pub build: u32,
+    pub changes: Vec<PaperChange>,
+}
+
+#[derive(Deserialize)]
+pub struct PaperChange {
+    pub commit: String,
+    pub summary: String,
+}
+
+// A single build from Purpur's API
+#[derive(Deserialize)]
+pub struct PurpurBuildInfo {
+    pub commits: Vec<PurpurCommit>,
+}
+
+#[derive(Deserialize)]
+pub struct PurpurCommit {
+    pub hash: String,
+    pub description: String,
+}
+
+/// Commit messages that went into a build.
+#[derive(Serialize, Clone, Debug)]
+pub struct BuildChanges {
+    pub build: u32,
+    pub changes: Vec<String>,
+}
+
 #[derive(Deserialize)]
 pub struct JarProjectInfo {
     pub project: Option<String>,
@@ -96,6 +137,8 @@ pub struct Jar {
     pub download_url: String,
     pub builds_url: String,
     pub versions_url: String,
+    #[serde(default)]
+    pub changelog_url: Option<String>,
 }
 
 pub trait JarBuildInfo {
@@ -109,6 +152,7 @@ impl Jar {
             download_url,
             builds_url,
             versions_url,
+            changelog_url: None,
         }
     }
 
@@ -137,6 +181,55 @@ impl Jar {
         Ok(builds)
     }
 
+    /// Changes of the given builds, newest first. Empty when the jar has no changelog.
+    pub fn get_changes(&self, version: &str, builds: &[u32]) -> Result<Vec<BuildChanges>, Error> {
+        let Some(changelog_url) = &self.changelog_url else {
+            return Ok(Vec::new());
+        };
+        let url = changelog_url.replace("{version}", version);
+        let lower_name = self.name.to_lowercase();
+        let mut changes: Vec<BuildChanges> = Vec::new();
+        if &lower_name == "paper" || &lower_name == "waterfall" || &lower_name == "velocity" {
+            let body = reqwest::blocking::get(url)?
+                .error_for_status()?
+                .json::<PaperBuildsInfo>()?;
+            changes = body
+                .builds
+                .into_iter()
+                .filter(|b| builds.contains(&b.build))
+                .map(|b| BuildChanges {
+                    build: b.build,
+                    changes: b
+                        .changes
+                        .into_iter()
+                        .map(|c| format!("{} {}", short_hash(&c.commit), c.summary))
+                        .collect(),
+                })
+                .collect();
+        } else if &lower_name == "purpur" {
+            // One request per build, Purpur doesn't list the commits of all builds at once
+            for build in builds {
+                let body = reqwest::blocking::get(url.replace("{build}", &build.to_string()))?
+                    .error_for_status()?
+                    .json::<PurpurBuildInfo>()?;
+                changes.push(BuildChanges {
+                    build: *build,
+                    changes: body
+                        .commits
+                        .into_iter()
+                        .map(|c| format!(
+                            "{} {}",
+                            short_hash(&c.hash),
+                            c.description.lines().next().unwrap_or_default()
+                        ))
+                        .collect(),
+                });
+            }
+        }
+        changes.sort_by_key(|c| std::cmp::Reverse(c.build));
+        Ok(changes)
+    }
+
     pub fn download(
         &self,
         version: &str,
@@ -144,8 +237,37 @@ impl Jar {
         server_name: &str,
         location: &Path,
     ) -> Result<Server, Error> {
+        self.download_jar(version, build, location)?;
+        Ok(Server::new(
+            server_name,
+            self.name.clone(),
+            version.to_string(),
+            build.to_string(),
+            location,
+        ))
+    }
+
+    // File name `Server::run` expects for this jar and version
+    pub fn file_name(&self, version: &str) -> String {
+        format!("{}-{}.jar", self.name, version)
+    }
+
+    /// Downloads the jar into `location`, replacing any jar with the same name only once
+    /// the download is complete.
+    pub fn download_jar(
+        &self,
+        version: &str,
+        build: &str,
+        location: &Path,
+    ) -> Result<PathBuf, Error> {
+        let path = location.join(self.file_name(version));
+        self.download_to(version, build, &path)?;
+        Ok(path)
+    }
+
+    /// Downloads the jar to `path`, which is only written once the download is complete.
+    pub fn download_to(&self, version: &str, build: &str, path: &Path) -> Result<(), Error> {
         println!("🗂️  Downloading {}...", colorize(&self.name, Color::Green));
-        let server: Server;
         let exec_time = get_exec_time!({
             let download_url = self
                 .download_url
@@ -156,25 +278,20 @@ impl Jar {
                 "🗂️  Downloading from {}...",
                 colorize(&download_url, Color::LightPurple)
             );
-            let response = reqwest::blocking::get(&download_url)?;
-            let mut file = File::create(location.join(format!("{}-{}.jar", self.name, version)))?;
+            let response = reqwest::blocking::get(&download_url)?.error_for_status()?;
+            let temp = path.with_extension("jar.part");
+            let mut file = File::create(&temp)?;
 
             download(response, &mut file);
-
-            server = Server::new(
-                server_name,
-                self.name.clone(),
-                version.to_string(),
-                build.to_string(),
-                location,
-            );
+            drop(file);
+            std::fs::rename(&temp, path)?;
         });
         println!(
             "🗂️  Downloaded {}! ({} elapsed)",
             colorize(&self.name, Color::Green),
             colorize(exec_time.as_str(), Color::Yellow)
         );
-        Ok(server)
+        Ok(())
     }
 
     pub fn get_versions(&self) -> Result<Vec<String>, Error> {
@@ -0,0 +1,151 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::minecraft::migration::Software;
+use crate::utils::parse_size;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+const AIKAR: [&str; 20] = [
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:G1RSetUpdatingPauseTimePercent=5",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+    "-Dusing.aikars.flags=https://mcflags.emc.gs",
+    "-Daikars.new.flags=true",
+];
+
+// Aikar's values for heaps over 12G, replacing the ones with the same option
+const AIKAR_LARGE_HEAP: [&str; 5] = [
+    "-XX:G1NewSizePercent=40",
+    "-XX:G1MaxNewSizePercent=50",
+    "-XX:G1HeapRegionSize=16M",
+    "-XX:G1ReservePercent=15",
+    "-XX:InitiatingHeapOccupancyPercent=20",
+];
+
+const ZGC_GENERATIONAL: [&str; 5] = [
+    "-XX:+UseZGC",
+    "-XX:+ZGenerational",
+    "-XX:+AlwaysPreTouch",
+    "-XX:+DisableExplicitGC",
+    "-XX:+PerfDisableSharedMem",
+];
+
+// Velocity's recommended flags
+const PROXY: [&str; 6] = [
+    "-XX:+UseG1GC",
+    "-XX:G1HeapRegionSize=4M",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:+AlwaysPreTouch",
+    "-XX:MaxInlineLevel=15",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum JvmProfile {
+    /// `proxy` for proxies, `aikar-large-heap` above 12G, `aikar` otherwise
+    #[default]
+    Auto,
+    Aikar,
+    /// Aikar's flags tuned for heaps over 12G
+    AikarLargeHeap,
+    /// Generational ZGC (Java 21+)
+    ZgcGenerational,
+    /// Flags for Velocity, Waterfall and other proxies
+    Proxy,
+    /// Only the memory settings
+    None,
+}
+
+impl JvmProfile {
+    /// The profile `auto` stands for, given the jar and maximum heap.
+    pub fn resolve(self, jar_name: &str, xmx: Option<&str>) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        if Software::of(jar_name) == Software::Proxy {
+            return Self::Proxy;
+        }
+        let large = xmx
+            .and_then(|xmx| parse_size(xmx).ok())
+            .is_some_and(|bytes| bytes > 12 << 30);
+        if large {
+            Self::AikarLargeHeap
+        } else {
+            Self::Aikar
+        }
+    }
+
+    pub fn flags(self) -> Vec<&'static str> {
+        match self {
+            Self::Auto | Self::Aikar => AIKAR.to_vec(),
+            Self::AikarLargeHeap => AIKAR
+                .iter()
+                .map(|flag| {
+                    let option = flag.split('=').next().unwrap_or(flag);
+                    AIKAR_LARGE_HEAP
+                        .iter()
+                        .find(|large| large.starts_with(&format!("{option}=")))
+                        .unwrap_or(flag)
+                })
+                .copied()
+                .collect(),
+            Self::ZgcGenerational => ZGC_GENERATIONAL.to_vec(),
+            Self::Proxy => PROXY.to_vec(),
+            Self::None => Vec::new(),
+        }
+    }
+}
+
+// [jvm] section of server_box.toml
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct JvmSettings {
+    #[serde(default)]
+    pub profile: JvmProfile,
+    // Added after the profile flags, before -jar
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    // Passed to the server after the jar
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Joins a command line for display, quoting the arguments a shell would split.
+pub fn shell_join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_=.:/+,@%".contains(c))
+            {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,456 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::jars::Jar;
+use crate::minecraft::jvm::JvmSettings;
+use crate::minecraft::memory::{self, DEFAULT_HEAP};
+use crate::minecraft::migration;
+use crate::minecraft::plugin_lock::{LockedPlugin, PluginLock};
+use crate::minecraft::plugin_sources::{self, parse_spec, PluginApis};
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::server_updates;
+use crate::utils::{colorize, hash_bytes, http_client, verify_hash, Color};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A server described in a checked-in file, which `boxes apply` converges the folder to.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub name: String,
+    // Relative to the manifest
+    pub location: PathBuf,
+    pub jar: String,
+    pub version: String,
+    // The latest build when the server is created, and left alone afterwards, if not set
+    pub build: Option<String>,
+    #[serde(default)]
+    pub accept_eula: bool,
+    pub memory: Option<MemoryManifest>,
+    pub jvm: Option<JvmSettings>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, toml::Value>,
+    // `slug` or `slug@version`, installed with their dependencies
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    #[serde(default)]
+    pub datapacks: Vec<Datapack>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryManifest {
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+// Stored as <level>/datapacks/<name>.zip
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Datapack {
+    pub name: String,
+    pub url: String,
+    pub sha256: String,
+}
+
+/// One difference between the manifest and the server folder.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Create {
+        jar: String,
+    },
+    Jar {
+        from: String,
+        to: String,
+    },
+    Setting {
+        key: String,
+        from: String,
+        to: String,
+    },
+    Property {
+        key: String,
+        from: Option<String>,
+        to: String,
+    },
+    Plugin {
+        spec: String,
+        from: Option<String>,
+    },
+    Datapack {
+        name: String,
+        exists: bool,
+    },
+    Register,
+}
+
+impl Change {
+    pub fn print(&self) {
+        let added = |text: &str| colorize(&format!("+ {text}"), Color::Green);
+        let changed = |text: &str| colorize(&format!("~ {text}"), Color::Yellow);
+        let line = match self {
+            Self::Create { jar } => added(&format!("create server with {jar}")),
+            Self::Jar { from, to } => changed(&format!("jar: {from} → {to}")),
+            Self::Setting { key, from, to } => changed(&format!("{key}: {from} → {to}")),
+            Self::Property {
+                key,
+                from: None,
+                to,
+            } => added(&format!("property {key}={to}")),
+            Self::Property {
+                key,
+                from: Some(from),
+                to,
+            } => changed(&format!("property {key}: {from} → {to}")),
+            Self::Plugin { spec, from: None } => added(&format!("plugin {spec}")),
+            Self::Plugin {
+                spec,
+                from: Some(from),
+            } => changed(&format!("plugin {spec} (installed: {from})")),
+            Self::Datapack {
+                name,
+                exists: false,
+            } => added(&format!("datapack {name}")),
+            Self::Datapack { name, exists: true } => changed(&format!("datapack {name}")),
+            Self::Register => added("add server to the boxes config"),
+        };
+        println!("  {line}");
+    }
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let mut manifest: Self = toml::from_str(&std::fs::read_to_string(path)?)?;
+        if manifest.location.is_relative() {
+            let base = path.parent().unwrap_or(Path::new("."));
+            manifest.location = base.join(&manifest.location);
+        }
+        Ok(manifest)
+    }
+
+    fn property(&self, key: &str) -> Option<String> {
+        self.properties.get(key).map(|value| match value {
+            toml::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        })
+    }
+
+    fn level_name(&self, server: Option<&Server>) -> String {
+        self.property("level-name")
+            .or_else(|| server.and_then(|server| manipulator(server).get_property("level-name")))
+            .unwrap_or_else(|| "world".to_string())
+    }
+
+    // Settings of server_box.toml the manifest sets, with the server's current values
+    fn settings(&self, server: Option<&Server>) -> Vec<(String, String, String)> {
+        let heap = |size: Option<&String>| {
+            let size = size.map_or(DEFAULT_HEAP, String::as_str);
+            memory::parse_heap(size).map_or_else(|_| size.to_string(), |(size, _)| size)
+        };
+        let mut settings = Vec::new();
+        if let Some(memory) = &self.memory {
+            let sizes = [
+                (
+                    "xms",
+                    memory.min.as_ref(),
+                    server.and_then(|s| s.xms.as_ref()),
+                ),
+                (
+                    "xmx",
+                    memory.max.as_ref(),
+                    server.and_then(|s| s.xmx.as_ref()),
+                ),
+            ];
+            for (key, wanted, current) in sizes {
+                if let Some(wanted) = wanted {
+                    settings.push((key.to_string(), heap(current), heap(Some(wanted))));
+                }
+            }
+        }
+        settings.push((
+            "accept_eula".to_string(),
+            server.is_some_and(|s| s.accept_eula).to_string(),
+            self.accept_eula.to_string(),
+        ));
+        if let Some(jvm) = &self.jvm {
+            let current = server.map(|s| s.jvm.clone()).unwrap_or_default();
+            settings.push((
+                "jvm.profile".to_string(),
+                format!("{:?}", current.profile),
+                format!("{:?}", jvm.profile),
+            ));
+            settings.push((
+                "jvm.jvm_args".to_string(),
+                current.jvm_args.join(" "),
+                jvm.jvm_args.join(" "),
+            ));
+            settings.push((
+                "jvm.args".to_string(),
+                current.args.join(" "),
+                jvm.args.join(" "),
+            ));
+        }
+        settings
+    }
+
+    fn apply_settings(&self, server: &mut Server) -> Result<(), Error> {
+        if let Some(memory) = &self.memory {
+            let min = memory.min.clone().or_else(|| server.xms.clone());
+            let max = memory.max.clone().or_else(|| server.xmx.clone());
+            let (min, max) = memory::validate(
+                min.as_deref().unwrap_or(DEFAULT_HEAP),
+                max.as_deref().unwrap_or(DEFAULT_HEAP),
+            )?;
+            server.xms = Some(min);
+            server.xmx = Some(max);
+        }
+        if let Some(jvm) = &self.jvm {
+            server.jvm = jvm.clone();
+        }
+        if self.accept_eula {
+            server.remember_eula()?;
+        } else {
+            server.accept_eula = false;
+        }
+        server.write();
+        Ok(())
+    }
+}
+
+fn manipulator(server: &Server) -> ServerManipulator {
+    ServerManipulator {
+        server: server.clone(),
+    }
+}
+
+// Plugins are matched by the slug they were installed with, or by project id
+fn locked<'a>(lock: &'a PluginLock, project: &str) -> Option<&'a LockedPlugin> {
+    lock.plugins.iter().find(|p| {
+        p.project_id == project
+            || p.slug
+                .as_deref()
+                .is_some_and(|slug| slug.eq_ignore_ascii_case(project))
+    })
+}
+
+fn datapack_path(manifest: &Manifest, server: Option<&Server>, datapack: &Datapack) -> PathBuf {
+    manifest
+        .location
+        .join(manifest.level_name(server))
+        .join("datapacks")
+        .join(format!("{}.zip", datapack.name))
+}
+
+// Plugins missing from plugins.lock or the plugins folder, or locked at another version
+fn plan_plugins(manifest: &Manifest, server: Option<&Server>) -> Result<Vec<Change>, Error> {
+    let lock = match server {
+        Some(server) => PluginLock::load(server)?,
+        None => PluginLock::default(),
+    };
+    let plugins = manifest.location.join("plugins");
+    let mut changes = Vec::new();
+    for spec in &manifest.plugins {
+        let (project, version) = parse_spec(spec);
+        let from = match locked(&lock, project) {
+            None => None,
+            Some(plugin) if version.is_some_and(|v| v != plugin.version) => {
+                Some(plugin.version.clone())
+            }
+            Some(plugin) if !plugin.matches(&plugins.join(&plugin.file_name))? => {
+                Some(format!("{}, jar missing", plugin.version))
+            }
+            Some(_) => continue,
+        };
+        changes.push(Change::Plugin {
+            spec: spec.clone(),
+            from,
+        });
+    }
+    Ok(changes)
+}
+
+/// What `apply` would change, in the order it changes it. Empty once the folder matches.
+pub fn plan(manifest: &Manifest, jar: &Jar, registered: bool) -> Result<Vec<Change>, Error> {
+    let mut changes = Vec::new();
+    let server = if manifest.location.join("server_box.toml").is_file() {
+        let server = Server::from_path(&manifest.location.to_string_lossy())?;
+        if server.server_name != manifest.name {
+            return Err(Error::ResourceNotFound(format!(
+                "{} holds the server {}, not {}",
+                manifest.location.display(),
+                server.server_name,
+                manifest.name
+            )));
+        }
+        Some(server)
+    } else {
+        changes.push(Change::Create {
+            jar: format!("{} {}", jar.name, manifest.version),
+        });
+        None
+    };
+
+    if let Some(server) = &server {
+        let same_software =
+            server.jar_name.eq_ignore_ascii_case(&jar.name) && server.version == manifest.version;
+        // Without a build the current one is kept, `latest` follows new builds
+        let build_differs = match manifest.build.as_deref() {
+            None => false,
+            Some(build) if build.eq_ignore_ascii_case("latest") => {
+                let (_, latest) =
+                    server_updates::resolve(jar, server, Some(&manifest.version), None)?;
+                latest != server.build
+            }
+            Some(build) => build != server.build,
+        };
+        if !same_software || build_differs {
+            changes.push(Change::Jar {
+                from: format!("{} {} #{}", server.jar_name, server.version, server.build),
+                to: format!(
+                    "{} {} #{}",
+                    jar.name,
+                    manifest.version,
+                    manifest.build.as_deref().unwrap_or("latest")
+                ),
+            });
+        }
+    }
+
+    for (key, from, to) in manifest.settings(server.as_ref()) {
+        if from != to {
+            changes.push(Change::Setting { key, from, to });
+        }
+    }
+
+    for key in manifest.properties.keys() {
+        let to = manifest.property(key).unwrap_or_default();
+        let from = server
+            .as_ref()
+            .and_then(|server| manipulator(server).get_property(key));
+        if from.as_deref() != Some(to.as_str()) {
+            changes.push(Change::Property {
+                key: key.clone(),
+                from,
+                to,
+            });
+        }
+    }
+
+    changes.extend(plan_plugins(manifest, server.as_ref())?);
+
+    for datapack in &manifest.datapacks {
+        let path = datapack_path(manifest, server.as_ref(), datapack);
+        let matches = path.is_file()
+            && hash_bytes("sha256", &std::fs::read(&path)?)?
+                .trim_start_matches("sha256:")
+                .eq_ignore_ascii_case(&datapack.sha256);
+        if !matches {
+            changes.push(Change::Datapack {
+                name: datapack.name.clone(),
+                exists: path.exists(),
+            });
+        }
+    }
+
+    if !registered {
+        changes.push(Change::Register);
+    }
+    Ok(changes)
+}
+
+/// Makes the changes of a plan. Jar changes take a backup first, like `upgrade` and `migrate`.
+pub fn apply(
+    manifest: &Manifest,
+    changes: &[Change],
+    jar: &Jar,
+    apis: &PluginApis,
+    config: &mut Config,
+) -> Result<Server, Error> {
+    let mut server = if manifest.location.join("server_box.toml").is_file() {
+        Some(Server::from_path(&manifest.location.to_string_lossy())?)
+    } else {
+        None
+    };
+    let mut settings_changed = false;
+    for change in changes {
+        match change {
+            Change::Create { .. } => {
+                std::fs::create_dir_all(&manifest.location)?;
+                let build = match manifest.build.as_deref() {
+                    Some(build) if !build.eq_ignore_ascii_case("latest") => build.to_string(),
+                    _ => jar
+                        .get_latest_build(&manifest.version)
+                        .ok_or(Error::ResourceNotFound("Jar build not found".to_string()))?
+                        .to_string(),
+                };
+                let location = crate::utils::canonize(&manifest.location)?;
+                server =
+                    Some(jar.download(&manifest.version, &build, &manifest.name, &location)?);
+            }
+            Change::Jar { .. } => {
+                let current = server.as_ref().ok_or(Error::Unknown)?;
+                let (version, build) = server_updates::resolve(
+                    jar,
+                    current,
+                    Some(&manifest.version),
+                    manifest.build.as_deref(),
+                )?;
+                let updated = if current.jar_name.eq_ignore_ascii_case(&jar.name) {
+                    server_updates::upgrade(current, jar, &version, &build)?.0
+                } else {
+                    migration::migrate(current, jar, &version, &build)?.server
+                };
+                server = Some(updated);
+            }
+            Change::Setting { .. } => settings_changed = true,
+            Change::Property { key, to, .. } => {
+                let current = server.as_ref().ok_or(Error::Unknown)?;
+                manipulator(current).set_property(key, to)?;
+            }
+            Change::Plugin { spec, .. } => {
+                let current = server.as_ref().ok_or(Error::Unknown)?;
+                plugin_sources::install(current, spec, None, apis)?;
+            }
+            Change::Datapack { name, .. } => {
+                let datapack = manifest
+                    .datapacks
+                    .iter()
+                    .find(|d| &d.name == name)
+                    .ok_or(Error::Unknown)?;
+                let path = datapack_path(manifest, server.as_ref(), datapack);
+                let bytes = http_client()?
+                    .get(&datapack.url)
+                    .send()?
+                    .error_for_status()?
+                    .bytes()?;
+                verify_hash(&bytes, &format!("sha256:{}", datapack.sha256))?;
+                std::fs::create_dir_all(path.parent().unwrap_or(&manifest.location))?;
+                let temp = path.with_extension("zip.part");
+                std::fs::write(&temp, bytes)?;
+                std::fs::rename(&temp, &path)?;
+            }
+            Change::Register => {
+                let current = server.as_ref().ok_or(Error::Unknown)?;
+                config.add_server(current, true);
+            }
+        }
+        // Settings go in right after the jar, before anything reads server_box.toml again
+        if settings_changed {
+            if let Some(current) = server.as_mut() {
+                manifest.apply_settings(current)?;
+                settings_changed = false;
+            }
+        }
+    }
+    server.ok_or(Error::ResourceNotFound("Nothing to apply".to_string()))
+}
@@ -0,0 +1,104 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::utils::parse_size;
+
+const KIB: u64 = 1 << 10;
+const MIB: u64 = 1 << 20;
+const GIB: u64 = 1 << 30;
+
+// What `Server::command_line` uses when xms or xmx isn't set
+pub const DEFAULT_HEAP: &str = "1G";
+
+/// Parses a heap size like any other size (512M, 1.5G, 6GB, a plain byte count), returning
+/// it in the largest unit `-Xms`/`-Xmx` take it in with its size in bytes.
+pub fn parse_heap(size: &str) -> Result<(String, u64), Error> {
+    let bytes = parse_size(size)?;
+    // The JVM refuses heaps under 2M
+    if bytes < 2 * MIB {
+        return Err(Error::InvalidInput(format!(
+            "Heap size {} is too small, use at least 2M",
+            size.trim()
+        )));
+    }
+    let normalized = [(GIB, "G"), (MIB, "M"), (KIB, "K")]
+        .iter()
+        .find(|(unit, _)| bytes % unit == 0)
+        .map_or_else(
+            || bytes.to_string(),
+            |(unit, suffix)| format!("{}{suffix}", bytes / unit),
+        );
+    Ok((normalized, bytes))
+}
+
+/// Validates a minimum and maximum heap together.
+pub fn validate(min: &str, max: &str) -> Result<(String, String), Error> {
+    let (min, min_bytes) = parse_heap(min)?;
+    let (max, max_bytes) = parse_heap(max)?;
+    if min_bytes > max_bytes {
+        return Err(Error::InvalidInput(format!(
+            "The minimum heap ({min}) is larger than the maximum ({max})"
+        )));
+    }
+    Ok((min, max))
+}
+
+// Maximum heap the server starts with, in bytes
+pub fn max_heap(server: &Server) -> u64 {
+    let xmx = server.xmx.as_deref().unwrap_or(DEFAULT_HEAP);
+    parse_heap(xmx).map_or(GIB, |(_, bytes)| bytes)
+}
+
+/// Total of the maximum heaps of the servers that start automatically.
+pub fn auto_start_total(servers: &[Server]) -> u64 {
+    servers
+        .iter()
+        .filter(|server| server.auto_start)
+        .map(max_heap)
+        .sum()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HostMemory {
+    pub total: u64,
+    pub available: u64,
+}
+
+/// Reads `MemTotal` and `MemAvailable` (in kB) from the contents of `/proc/meminfo`.
+pub fn parse_meminfo(meminfo: &str) -> Option<HostMemory> {
+    let value = |key: &str| {
+        meminfo.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            (k.trim() == key)
+                .then(|| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+                .flatten()
+                .map(|kb| kb * 1024)
+        })
+    };
+    let total = value("MemTotal")?;
+    Some(HostMemory {
+        total,
+        available: value("MemAvailable").unwrap_or(total),
+    })
+}
+
+// Physical memory of the host, on systems with /proc/meminfo
+pub fn host_memory() -> Option<HostMemory> {
+    parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+/// Heap to suggest given the available memory: three quarters of it, leaving room for the
+/// JVM itself and the OS, in whole gigabytes between 1G and 16G. Xms matches Xmx, as
+/// Aikar's flags recommend.
+pub fn suggest(available: u64) -> String {
+    let gigabytes = (available / 4 * 3 / GIB).clamp(1, 16);
+    format!("{gigabytes}G")
+}
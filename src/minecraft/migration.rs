@@ -0,0 +1,223 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::backup::{self, BackupManifest};
+use crate::minecraft::jars::Jar;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::server_updates::{stage_jar, swap_jar};
+use crate::utils::move_dir;
+use std::path::{Path, PathBuf};
+
+// Nether and end folders: the suffix of the Bukkit world and the vanilla dimension folder
+const DIMENSIONS: [(&str, &str); 2] = [("_nether", "DIM-1"), ("_the_end", "DIM1")];
+
+// Files Bukkit keeps in each dimension world that vanilla doesn't need
+const BUKKIT_WORLD_FILES: [&str; 4] = ["level.dat", "level.dat_old", "uid.dat", "session.lock"];
+
+/// Server software families. Each one reads the config files of those before it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Software {
+    Vanilla,
+    Bukkit,
+    Spigot,
+    Paper,
+    Purpur,
+    Proxy,
+}
+
+impl Software {
+    pub fn of(jar_name: &str) -> Self {
+        match jar_name.to_lowercase().as_str() {
+            "bukkit" | "craftbukkit" => Self::Bukkit,
+            "spigot" => Self::Spigot,
+            "paper" | "folia" | "pufferfish" => Self::Paper,
+            "purpur" => Self::Purpur,
+            "velocity" | "waterfall" | "bungeecord" => Self::Proxy,
+            _ => Self::Vanilla,
+        }
+    }
+
+    // Bukkit-based servers load plugins/ and split dimensions into their own worlds
+    pub fn is_bukkit(self) -> bool {
+        self >= Self::Bukkit && self != Self::Proxy
+    }
+
+    /// Config files (on top of `server.properties`) this software reads.
+    pub fn config_files(self) -> Vec<&'static str> {
+        let layers: [(Self, &[&str]); 4] = [
+            (
+                Self::Bukkit,
+                &["bukkit.yml", "commands.yml", "permissions.yml", "help.yml"],
+            ),
+            (Self::Spigot, &["spigot.yml"]),
+            (Self::Paper, &["config", "paper.yml"]),
+            (Self::Purpur, &["purpur.yml"]),
+        ];
+        if self == Self::Proxy {
+            return Vec::new();
+        }
+        layers
+            .iter()
+            .filter(|(software, _)| *software <= self)
+            .flat_map(|(_, files)| files.iter().copied())
+            .collect()
+    }
+}
+
+pub struct MigrationReport {
+    pub backup: BackupManifest,
+    pub server: Server,
+    // Dimension folders moved to the target's layout
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    // Config files the old software read that the new one ignores
+    pub unused_configs: Vec<String>,
+}
+
+/// Whether switching to `jar` leaves the server's plugins without a loader.
+pub fn orphans_plugins(server: &Server, jar: &Jar) -> bool {
+    let plugins = server.location.join("plugins");
+    Software::of(&server.jar_name).is_bukkit()
+        && !Software::of(&jar.name).is_bukkit()
+        && plugins
+            .read_dir()
+            .is_ok_and(|mut entries| entries.next().is_some())
+}
+
+/// Switches a stopped server to another software. A backup is taken first, world folders
+/// are moved to the layout the new software expects and config files stay in place.
+pub fn migrate(
+    server: &Server,
+    jar: &Jar,
+    version: &str,
+    build: &str,
+) -> Result<MigrationReport, Error> {
+    if server.is_running() {
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    let from = Software::of(&server.jar_name);
+    let to = Software::of(&jar.name);
+    if server.jar_name.eq_ignore_ascii_case(&jar.name) {
+        return Err(Error::ResourceNotFound(format!(
+            "{} already runs {}, use upgrade to change its version",
+            server.server_name, jar.name
+        )));
+    }
+    if (from == Software::Proxy) != (to == Software::Proxy) {
+        return Err(Error::ResourceNotFound(format!(
+            "Can't migrate between a proxy and a game server ({} to {})",
+            server.jar_name, jar.name
+        )));
+    }
+
+    println!("🗄️ Taking a backup before migrating...");
+    let backup = backup::create(server, server.backup.format, false)?;
+    // Worlds only move once the new jar is here, the old software can't run a moved layout
+    let staged = stage_jar(server, jar, version, build)?;
+    let level = ServerManipulator {
+        server: server.clone(),
+    }
+    .get_property("level-name")
+    .unwrap_or_else(|| "world".to_string());
+    let moved = match (from.is_bukkit(), to.is_bukkit()) {
+        (false, true) if from != Software::Proxy => to_bukkit_layout(&server.location, &level),
+        (true, false) => to_vanilla_layout(&server.location, &level),
+        _ => Ok(Vec::new()),
+    };
+    let moved = match moved {
+        Ok(moved) => moved,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e);
+        }
+    };
+    let used = to.config_files();
+    let unused_configs = from
+        .config_files()
+        .into_iter()
+        .filter(|file| !used.contains(file) && server.location.join(file).exists())
+        .map(str::to_string)
+        .collect();
+    let server = swap_jar(server, jar, version, build, &staged)?;
+    Ok(MigrationReport {
+        backup,
+        server,
+        moved,
+        unused_configs,
+    })
+}
+
+/// Moves `<level>/DIM-1` and `<level>/DIM1` into the `<level>_nether` and `<level>_the_end`
+/// worlds Bukkit loads them from.
+pub fn to_bukkit_layout(location: &Path, level: &str) -> Result<Vec<(PathBuf, PathBuf)>, Error> {
+    let moves: Vec<(PathBuf, PathBuf)> = DIMENSIONS
+        .iter()
+        .map(|(suffix, dimension)| {
+            (
+                location.join(level).join(dimension),
+                location.join(format!("{level}{suffix}")).join(dimension),
+            )
+        })
+        .filter(|(from, _)| from.is_dir())
+        .collect();
+    check_targets(&moves)?;
+    for (from, to) in &moves {
+        let world = to.parent().unwrap_or(location);
+        std::fs::create_dir_all(world)?;
+        move_dir(from, to)?;
+        // Bukkit only loads a folder with a level.dat as a world
+        let level_dat = location.join(level).join("level.dat");
+        if level_dat.is_file() && !world.join("level.dat").exists() {
+            std::fs::copy(&level_dat, world.join("level.dat"))?;
+        }
+    }
+    Ok(moves)
+}
+
+/// Moves the dimensions of the `<level>_nether` and `<level>_the_end` worlds back into
+/// `<level>`, removing the Bukkit worlds once nothing else is left in them.
+pub fn to_vanilla_layout(location: &Path, level: &str) -> Result<Vec<(PathBuf, PathBuf)>, Error> {
+    let moves: Vec<(PathBuf, PathBuf)> = DIMENSIONS
+        .iter()
+        .map(|(suffix, dimension)| {
+            (
+                location.join(format!("{level}{suffix}")).join(dimension),
+                location.join(level).join(dimension),
+            )
+        })
+        .filter(|(from, _)| from.is_dir())
+        .collect();
+    check_targets(&moves)?;
+    for (from, to) in &moves {
+        std::fs::create_dir_all(location.join(level))?;
+        move_dir(from, to)?;
+        let world = from.parent().unwrap_or(location);
+        for file in BUKKIT_WORLD_FILES {
+            if world.join(file).is_file() {
+                std::fs::remove_file(world.join(file))?;
+            }
+        }
+        if world.read_dir()?.next().is_none() {
+            std::fs::remove_dir(world)?;
+        }
+    }
+    Ok(moves)
+}
+
+// Refuses before anything is moved so a conversion is never left half done
+fn check_targets(moves: &[(PathBuf, PathBuf)]) -> Result<(), Error> {
+    match moves.iter().find(|(_, to)| to.exists()) {
+        Some((_, to)) => Err(Error::ResourceNotFound(format!(
+            "{} already exists, move it away first",
+            to.display()
+        ))),
+        None => Ok(()),
+    }
+}
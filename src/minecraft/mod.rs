@@ -1,3 +1,20 @@
+pub(crate) mod backup;
+pub(crate) mod console;
+pub(crate) mod instances;
 pub(crate) mod jars;
+pub(crate) mod jvm;
+pub(crate) mod manifest;
+pub(crate) mod memory;
+pub(crate) mod migration;
+pub(crate) mod network;
+pub(crate) mod player_lists;
+pub(crate) mod plugin_lock;
+pub(crate) mod plugin_sources;
+pub(crate) mod plugin_updates;
+pub(crate) mod plugins;
+pub(crate) mod repository;
 pub(crate) mod server;
 pub(crate) mod server_manipulator;
+pub(crate) mod server_updates;
+pub(crate) mod templates;
+pub(crate) mod trash;
@@ -0,0 +1,322 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::console::{self, Console};
+use crate::minecraft::instances::free_port;
+use crate::minecraft::migration::Software;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::utils::compare_versions;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use toml_edit::{value, Array, Document, Item, Table};
+
+const PROXY_PORT: u16 = 25565;
+const FIRST_BACKEND_PORT: u16 = 25566;
+const SECRET_FILE: &str = "forwarding.secret";
+const SECRET_LENGTH: usize = 24;
+const SECRET_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+// Both Paper and Velocity log this once they accept players
+const READY_LINE: &str = "Done (";
+// Modern forwarding came with Paper 1.13, its settings moved to paper-global.yml in 1.19
+const FIRST_FORWARDING_VERSION: &str = "1.13";
+const GLOBAL_CONFIG_VERSION: &str = "1.19";
+const START_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A Velocity proxy and the backends it forwards players to, stored in the boxes config.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Network {
+    pub name: String,
+    pub proxy: String,
+    // In the order players are sent to them (Velocity's `try` list)
+    pub backends: Vec<String>,
+}
+
+impl Network {
+    pub fn print(&self) {
+        println!(
+            "  🕸️ {}: {} → {}",
+            self.name,
+            self.proxy,
+            self.backends.join(", ")
+        );
+    }
+}
+
+/// Ports given to the servers of a network.
+pub struct Wiring {
+    pub proxy_port: u16,
+    pub backend_ports: Vec<(String, u16)>,
+}
+
+/// Points the proxy at the backends and the backends at the proxy: `[servers]` and `try` in
+/// velocity.toml, modern forwarding with a shared secret, `online-mode=false` and
+/// `proxies.velocity` in paper-global.yml (`settings.velocity-support` in paper.yml before
+/// 1.19). Every server gets a port not in `taken`.
+pub fn wire(proxy: &Server, backends: &[Server], taken: &[u16]) -> Result<Wiring, Error> {
+    if !proxy.jar_name.eq_ignore_ascii_case("velocity") {
+        return Err(Error::InvalidInput(format!(
+            "{} runs {}, the proxy of a network must run Velocity",
+            proxy.server_name, proxy.jar_name
+        )));
+    }
+    if backends.is_empty() {
+        return Err(Error::InvalidInput(
+            "A network needs at least one backend".to_string(),
+        ));
+    }
+    for server in std::iter::once(proxy).chain(backends) {
+        if server.is_running() {
+            return Err(Error::ServerRunning(server.server_name.clone()));
+        }
+    }
+    for backend in backends {
+        if !matches!(
+            Software::of(&backend.jar_name),
+            Software::Paper | Software::Purpur
+        ) {
+            return Err(Error::InvalidInput(format!(
+                "{} runs {}, backends must run Paper or Purpur for Velocity forwarding",
+                backend.server_name, backend.jar_name
+            )));
+        }
+        if compare_versions(&backend.version, FIRST_FORWARDING_VERSION) == Ordering::Less {
+            return Err(Error::InvalidInput(format!(
+                "{} runs {}, Velocity forwarding needs {FIRST_FORWARDING_VERSION} or newer",
+                backend.server_name, backend.version
+            )));
+        }
+    }
+
+    let mut taken = taken.to_vec();
+    let proxy_port = free_port(PROXY_PORT, &taken)?;
+    taken.push(proxy_port);
+    let mut backend_ports = Vec::new();
+    for backend in backends {
+        let port = free_port(FIRST_BACKEND_PORT, &taken)?;
+        taken.push(port);
+        backend_ports.push((backend.server_name.clone(), port));
+    }
+
+    let secret = forwarding_secret(&proxy.location)?;
+    write_velocity_toml(&proxy.location, proxy_port, &backend_ports)?;
+    for (backend, (_, port)) in backends.iter().zip(&backend_ports) {
+        let manipulator = ServerManipulator {
+            server: backend.clone(),
+        };
+        manipulator.set_property("server-port", &port.to_string())?;
+        manipulator.set_property("online-mode", "false")?;
+        enable_velocity_forwarding(backend, &secret)?;
+    }
+    Ok(Wiring {
+        proxy_port,
+        backend_ports,
+    })
+}
+
+// The proxy's forwarding.secret, created with a random secret the first time
+fn forwarding_secret(proxy: &Path) -> Result<String, Error> {
+    let path = proxy.join(SECRET_FILE);
+    if let Ok(secret) = std::fs::read_to_string(&path) {
+        if !secret.trim().is_empty() {
+            return Ok(secret.trim().to_string());
+        }
+    }
+    // Bytes from 248 up are dropped, the alphabet fits in the rest 4 times over without bias
+    let limit = 256 - 256 % SECRET_ALPHABET.len();
+    let mut secret = String::with_capacity(SECRET_LENGTH);
+    while secret.len() < SECRET_LENGTH {
+        let mut bytes = [0u8; SECRET_LENGTH];
+        getrandom::getrandom(&mut bytes)
+            .map_err(|e| Error::ResourceNotFound(format!("No randomness for the secret: {e}")))?;
+        secret.extend(
+            bytes
+                .iter()
+                .map(|b| usize::from(*b))
+                .filter(|b| *b < limit)
+                .map(|b| char::from(SECRET_ALPHABET[b % SECRET_ALPHABET.len()]))
+                .take(SECRET_LENGTH - secret.len()),
+        );
+    }
+    std::fs::write(path, &secret)?;
+    Ok(secret)
+}
+
+// Edits velocity.toml in place, keeping its comments (or starts one if Velocity never ran)
+fn write_velocity_toml(proxy: &Path, port: u16, backends: &[(String, u16)]) -> Result<(), Error> {
+    let path = proxy.join("velocity.toml");
+    let mut document = match std::fs::read_to_string(&path) {
+        Ok(text) => text
+            .parse::<Document>()
+            .map_err(|e| Error::ResourceNotFound(format!("Invalid velocity.toml: {e}")))?,
+        Err(_) => Document::new(),
+    };
+    document["bind"] = value(format!("0.0.0.0:{port}"));
+    document["player-info-forwarding-mode"] = value("modern");
+    document["forwarding-secret-file"] = value(SECRET_FILE);
+
+    let mut servers = Table::new();
+    let mut order = Array::new();
+    for (name, port) in backends {
+        servers[name.as_str()] = value(format!("127.0.0.1:{port}"));
+        order.push(name.as_str());
+    }
+    servers["try"] = value(order);
+    document["servers"] = Item::Table(servers);
+
+    // Velocity refuses forced hosts pointing at servers it doesn't know
+    if let Some(forced) = document
+        .get_mut("forced-hosts")
+        .and_then(Item::as_table_mut)
+    {
+        forced.retain(|_, hosts| {
+            hosts.as_array().is_some_and(|hosts| {
+                hosts.iter().all(|h| {
+                    h.as_str()
+                        .is_some_and(|h| backends.iter().any(|(n, _)| n == h))
+                })
+            })
+        });
+    }
+    std::fs::write(path, document.to_string())?;
+    Ok(())
+}
+
+// Sets proxies.velocity in config/paper-global.yml, or settings.velocity-support in
+// paper.yml before 1.19
+fn enable_velocity_forwarding(backend: &Server, secret: &str) -> Result<(), Error> {
+    let (path, section) =
+        if compare_versions(&backend.version, GLOBAL_CONFIG_VERSION) == Ordering::Less {
+            (
+                backend.location.join("paper.yml"),
+                ["settings", "velocity-support"],
+            )
+        } else {
+            (
+                backend.location.join("config").join("paper-global.yml"),
+                ["proxies", "velocity"],
+            )
+        };
+    let mut global: Value = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_yaml::from_str(&text)?,
+        Err(_) => Value::Mapping(Mapping::new()),
+    };
+    let velocity = child(child(&mut global, section[0]), section[1]);
+    velocity["enabled"] = Value::Bool(true);
+    velocity["online-mode"] = Value::Bool(true);
+    velocity["secret"] = Value::String(secret.to_string());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_yaml::to_string(&global)?)?;
+    Ok(())
+}
+
+// The mapping under `key`, created when missing
+fn child<'a>(parent: &'a mut Value, key: &str) -> &'a mut Value {
+    if !parent.is_mapping() {
+        *parent = Value::Mapping(Mapping::new());
+    }
+    let mapping = parent.as_mapping_mut().expect("just made a mapping");
+    let entry = mapping
+        .entry(Value::String(key.to_string()))
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    if !entry.is_mapping() {
+        *entry = Value::Mapping(Mapping::new());
+    }
+    entry
+}
+
+/// Starts the backends one after the other, then the proxy, each in its own `boxes start`
+/// process. Every server has to log that it's ready before the next one starts.
+pub fn start(proxy: &Server, backends: &[Server], accept_eula: bool) -> Result<(), Error> {
+    // The servers can't ask about the EULA with their stdin closed, so it's settled first
+    for server in backends.iter().chain([proxy]) {
+        let needs_eula = Software::of(&server.jar_name) != Software::Proxy;
+        if !needs_eula || server.is_running() || server.eula_accepted() || server.accept_eula {
+            continue;
+        }
+        if !accept_eula {
+            return Err(Error::InvalidInput(format!(
+                "{} hasn't accepted the EULA, start the network with --accept-eula",
+                server.server_name
+            )));
+        }
+        server.accept_eula()?;
+    }
+
+    let exe = std::env::current_exe()?;
+    for server in backends.iter().chain([proxy]) {
+        if server.is_running() {
+            println!("⏭️ {} is already running", server.server_name);
+            continue;
+        }
+        println!("🚀 Starting {}...", server.server_name);
+        let log = console::log_file(server);
+        let offset = log.metadata().map_or(0, |m| m.len());
+        let output_path = server.location.join("logs").join("boxes.log");
+        std::fs::create_dir_all(server.location.join("logs"))?;
+        let output = std::fs::File::create(&output_path)?;
+        let mut child = Command::new(&exe)
+            .args(["start", &server.server_name])
+            .stdin(Stdio::null())
+            .stdout(output.try_clone()?)
+            .stderr(output)
+            .spawn()?;
+        console::wait_for_log_while(&log, offset, READY_LINE, START_TIMEOUT, || {
+            match child.try_wait()? {
+                Some(status) => Err(Error::Console(format!(
+                    "{} exited ({status}) before it was up, see {}",
+                    server.server_name,
+                    output_path.display()
+                ))),
+                None => Ok(()),
+            }
+        })?;
+        println!("✅ {} is up", server.server_name);
+    }
+    Ok(())
+}
+
+/// Stops the proxy first so players aren't sent to stopping backends, then the backends in
+/// reverse order.
+pub fn stop(proxy: &Server, backends: &[Server]) -> Result<(), Error> {
+    for server in [proxy].into_iter().chain(backends.iter().rev()) {
+        if !server.is_running() {
+            println!("⏭️ {} is not running", server.server_name);
+            continue;
+        }
+        println!("🛑 Stopping {}...", server.server_name);
+        let command = if Software::of(&server.jar_name) == Software::Proxy {
+            "shutdown"
+        } else {
+            "stop"
+        };
+        Console::connect(server)?.send(command)?;
+        let started = Instant::now();
+        while server.is_running() {
+            if started.elapsed() > STOP_TIMEOUT {
+                return Err(Error::Console(format!(
+                    "{} did not stop in time",
+                    server.server_name
+                )));
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        println!("✅ {} stopped", server.server_name);
+    }
+    Ok(())
+}
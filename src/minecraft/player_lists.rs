@@ -0,0 +1,248 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, Color};
+use clap::ValueEnum;
+use serde_json::Value;
+use std::fmt::Display;
+
+// Integration to read/write the vanilla player list files (whitelist, ops, bans)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PlayerList {
+    Whitelist,
+    Ops,
+    BannedPlayers,
+    BannedIps,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SyncMode {
+    /// Add the source entries to each target and keep the target's own entries
+    #[default]
+    Merge,
+    /// Make each target an exact copy of the source
+    Mirror,
+}
+
+impl PlayerList {
+    pub fn all() -> Vec<PlayerList> {
+        vec![
+            PlayerList::Whitelist,
+            PlayerList::Ops,
+            PlayerList::BannedPlayers,
+            PlayerList::BannedIps,
+        ]
+    }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            PlayerList::Whitelist => "whitelist.json",
+            PlayerList::Ops => "ops.json",
+            PlayerList::BannedPlayers => "banned-players.json",
+            PlayerList::BannedIps => "banned-ips.json",
+        }
+    }
+
+    // Field used to identify the same entry across servers
+    fn key_field(self) -> &'static str {
+        match self {
+            PlayerList::BannedIps => "ip",
+            _ => "uuid",
+        }
+    }
+
+    pub fn read(self, server: &Server) -> Result<Vec<Value>, Error> {
+        let path = server.location.join(self.file_name());
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = std::fs::read_to_string(path)?;
+        if file.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let entries: Vec<Value> = serde_json::from_str(&file)?;
+        Ok(entries)
+    }
+
+    pub fn write(self, server: &Server, entries: &[Value]) -> Result<(), Error> {
+        let path = server.location.join(self.file_name());
+        let file = serde_json::to_string_pretty(entries)?;
+        std::fs::write(path, file)?;
+        Ok(())
+    }
+
+    // Fields that make two entries differ. Bookkeeping like `created`, `source` and
+    // `expires` is left out since every server stamps its own.
+    fn compared_fields(self) -> &'static [&'static str] {
+        match self {
+            PlayerList::Whitelist => &["uuid", "name"],
+            PlayerList::Ops => &["uuid", "name", "level", "bypassesPlayerLimit"],
+            PlayerList::BannedPlayers => &["uuid", "name", "reason"],
+            PlayerList::BannedIps => &["ip", "reason"],
+        }
+    }
+
+    fn key(self, entry: &Value) -> String {
+        entry
+            .get(self.key_field())
+            .or_else(|| entry.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+impl Display for PlayerList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file_name())
+    }
+}
+
+pub struct Conflict {
+    pub entry: String,
+    pub fields: Vec<String>,
+}
+
+pub struct SyncReport {
+    pub target: String,
+    pub list: PlayerList,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl SyncReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.conflicts.is_empty()
+    }
+
+    pub fn print(&self) {
+        let header = format!("{} ➥ {}", self.target, self.list);
+        if self.is_empty() {
+            println!("  📋 {} (in sync)", colorize(&header, Color::Gold));
+            return;
+        }
+        println!("  📋 {}", colorize(&header, Color::Gold));
+        for entry in &self.added {
+            println!("      {} {entry}", colorize("+", Color::Green));
+        }
+        for entry in &self.removed {
+            println!("      {} {entry}", colorize("-", Color::Red));
+        }
+        for conflict in &self.conflicts {
+            println!(
+                "      {} {} (differs in: {})",
+                colorize("!", Color::Yellow),
+                conflict.entry,
+                conflict.fields.join(", ")
+            );
+        }
+    }
+}
+
+fn display_name(entry: &Value, key: &str) -> String {
+    match entry.get("name").and_then(Value::as_str) {
+        Some(name) => format!("{name} ({key})"),
+        None => key.to_string(),
+    }
+}
+
+fn differing_fields(list: PlayerList, source: &Value, target: &Value) -> Vec<String> {
+    let (Some(source), Some(target)) = (source.as_object(), target.as_object()) else {
+        return vec!["<entry>".to_string()];
+    };
+    let mut fields: Vec<String> = list
+        .compared_fields()
+        .iter()
+        .filter(|key| source.get(**key) != target.get(**key))
+        .map(ToString::to_string)
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// Computes the new contents of a target list. The source always wins when
+/// both sides have an entry for the same player (or IP) with different data.
+/// Only the fields of `PlayerList::compared_fields` count as different data.
+pub fn plan_sync(
+    list: PlayerList,
+    mode: SyncMode,
+    target_name: &str,
+    source: &[Value],
+    target: &[Value],
+) -> (Vec<Value>, SyncReport) {
+    let mut report = SyncReport {
+        target: target_name.to_string(),
+        list,
+        added: Vec::new(),
+        removed: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    let mut result: Vec<Value> = Vec::new();
+
+    for entry in source {
+        let key = list.key(entry);
+        match target.iter().find(|t| list.key(t) == key) {
+            None => report.added.push(display_name(entry, &key)),
+            Some(existing) => {
+                let fields = differing_fields(list, entry, existing);
+                if !fields.is_empty() {
+                    report.conflicts.push(Conflict {
+                        entry: display_name(entry, &key),
+                        fields,
+                    });
+                }
+            }
+        }
+        result.push(entry.clone());
+    }
+
+    for entry in target {
+        let key = list.key(entry);
+        if source.iter().any(|s| list.key(s) == key) {
+            continue;
+        }
+        match mode {
+            SyncMode::Merge => result.push(entry.clone()),
+            SyncMode::Mirror => report.removed.push(display_name(entry, &key)),
+        }
+    }
+
+    (result, report)
+}
+
+pub fn sync(
+    source: &Server,
+    targets: &[Server],
+    lists: &[PlayerList],
+    mode: SyncMode,
+    dry_run: bool,
+) -> Result<Vec<SyncReport>, Error> {
+    let mut reports = Vec::new();
+    for list in lists {
+        let source_entries = list.read(source)?;
+        for target in targets {
+            let target_entries = list.read(target)?;
+            let (entries, report) = plan_sync(
+                *list,
+                mode,
+                &target.server_name,
+                &source_entries,
+                &target_entries,
+            );
+            if !dry_run && !report.is_empty() {
+                list.write(target, &entries)?;
+            }
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
@@ -0,0 +1,186 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::plugin_sources::{Platform, PluginApis, PluginRelease, PluginSource};
+use crate::minecraft::plugins::DISABLED_DIR;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, hash_bytes, Color};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE: &str = "plugins.lock";
+
+// plugins.lock, stored next to server_box.toml
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct PluginLock {
+    #[serde(default)]
+    pub plugins: Vec<LockedPlugin>,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct LockedPlugin {
+    pub source: PluginSource,
+    pub project_id: String,
+    // Missing from lockfiles written before slugs were recorded
+    #[serde(default)]
+    pub slug: Option<String>,
+    pub name: String,
+    pub version: String,
+    pub file_name: String,
+    pub hash: String,
+}
+
+#[derive(Default)]
+pub struct LockSyncReport {
+    pub up_to_date: Vec<String>,
+    pub disabled: Vec<String>,
+    pub installed: Vec<String>,
+    pub unmanaged: Vec<String>,
+}
+
+impl LockedPlugin {
+    pub fn from_release(release: &PluginRelease) -> Self {
+        Self {
+            source: release.source,
+            project_id: release.project_id.clone(),
+            slug: Some(release.slug.clone()),
+            name: release.name.clone(),
+            version: release.version.clone(),
+            file_name: release.file_name.clone(),
+            hash: release.hash.clone(),
+        }
+    }
+
+    // Whether the file at `path` is exactly the locked jar
+    pub fn matches(&self, path: &Path) -> Result<bool, Error> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+        let algorithm = self.hash.split(':').next().unwrap_or_default();
+        let hash = hash_bytes(algorithm, &std::fs::read(path)?)?;
+        Ok(hash.eq_ignore_ascii_case(&self.hash))
+    }
+}
+
+impl PluginLock {
+    pub fn path(server: &Server) -> PathBuf {
+        server.location.join(LOCK_FILE)
+    }
+
+    pub fn load(server: &Server) -> Result<Self, Error> {
+        Self::load_from(&Self::path(server))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let lock = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&lock)?)
+    }
+
+    pub fn save(&self, server: &Server) -> Result<(), Error> {
+        let lock = toml::to_string(self)?;
+        std::fs::write(Self::path(server), lock)?;
+        Ok(())
+    }
+
+    pub fn get(&self, project_id: &str) -> Option<&LockedPlugin> {
+        self.plugins.iter().find(|p| p.project_id == project_id)
+    }
+
+    // Adds a plugin, replacing any entry for the same project
+    pub fn upsert(&mut self, plugin: LockedPlugin) {
+        self.plugins.retain(|p| p.project_id != plugin.project_id);
+        self.plugins.push(plugin);
+        self.plugins.sort_by_key(|p| p.name.to_lowercase());
+    }
+
+    pub fn remove(&mut self, project_id: &str) -> Option<LockedPlugin> {
+        let index = self
+            .plugins
+            .iter()
+            .position(|p| p.project_id == project_id)?;
+        Some(self.plugins.remove(index))
+    }
+
+    pub fn remove_file(&mut self, file_name: &str) -> Option<LockedPlugin> {
+        let index = self.plugins.iter().position(|p| p.file_name == file_name)?;
+        Some(self.plugins.remove(index))
+    }
+
+    pub fn is_managed(&self, file_name: &str) -> bool {
+        self.plugins.iter().any(|p| p.file_name == file_name)
+    }
+
+    /// Downloads every locked plugin that is missing or differs from the lockfile.
+    /// Jars that the lockfile doesn't know about are reported, never deleted.
+    pub fn sync(&self, server: &Server, apis: &PluginApis) -> Result<LockSyncReport, Error> {
+        let platform = Platform::of(server)?;
+        let dir = server.location.join("plugins");
+        std::fs::create_dir_all(&dir)?;
+        let mut report = LockSyncReport::default();
+
+        for plugin in &self.plugins {
+            let path = dir.join(&plugin.file_name);
+            if plugin.matches(&path)? {
+                report.up_to_date.push(plugin.name.clone());
+                continue;
+            }
+            if plugin.matches(&dir.join(DISABLED_DIR).join(&plugin.file_name))? {
+                report.disabled.push(plugin.name.clone());
+                continue;
+            }
+            let mut release = apis.find(
+                &plugin.project_id,
+                Some(&plugin.version),
+                Some(plugin.source),
+                &platform,
+            )?;
+            // The lockfile is the source of truth for what ends up on disk
+            release.hash.clone_from(&plugin.hash);
+            let bytes = apis.download(&release)?;
+            let temp = dir.join(format!(".{}.part", plugin.file_name));
+            std::fs::write(&temp, bytes)?;
+            std::fs::rename(&temp, &path)?;
+            report
+                .installed
+                .push(format!("{} {}", plugin.name, plugin.version));
+        }
+
+        for file in server.plugins() {
+            let file = file.to_string_lossy().to_string();
+            if !self.is_managed(&file) {
+                report.unmanaged.push(file);
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl LockSyncReport {
+    pub fn print(&self) {
+        for plugin in &self.up_to_date {
+            println!("  ✅ {plugin} is up to date");
+        }
+        for plugin in &self.disabled {
+            println!("  💤 {plugin} is disabled");
+        }
+        for plugin in &self.installed {
+            println!("  📦 Installed {}", colorize(plugin, Color::Green));
+        }
+        for file in &self.unmanaged {
+            println!(
+                "  ⚠️ {} is not in plugins.lock (left untouched)",
+                colorize(file, Color::Yellow)
+            );
+        }
+    }
+}
@@ -0,0 +1,615 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::plugin_lock::{LockedPlugin, PluginLock};
+use crate::minecraft::plugins;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, http_client, verify_hash, Color};
+use clap::ValueEnum;
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginSource {
+    Modrinth,
+    Hangar,
+}
+
+impl Display for PluginSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginSource::Modrinth => write!(f, "Modrinth"),
+            PluginSource::Hangar => write!(f, "Hangar"),
+        }
+    }
+}
+
+// What a server can load, derived from its jar
+pub struct Platform {
+    pub loaders: Vec<&'static str>,
+    pub hangar: Option<&'static str>,
+    pub game_version: Option<String>,
+}
+
+impl Platform {
+    pub fn of(server: &Server) -> Result<Self, Error> {
+        let (loaders, hangar, proxy): (Vec<&str>, Option<&str>, bool) =
+            match server.jar_name.to_lowercase().as_str() {
+                "paper" => (vec!["paper", "spigot", "bukkit"], Some("PAPER"), false),
+                "purpur" => (
+                    vec!["purpur", "paper", "spigot", "bukkit"],
+                    Some("PAPER"),
+                    false,
+                ),
+                "folia" => (vec!["folia"], Some("PAPER"), false),
+                "spigot" | "bukkit" | "craftbukkit" => (vec!["spigot", "bukkit"], None, false),
+                "velocity" => (vec!["velocity"], Some("VELOCITY"), true),
+                "waterfall" => (vec!["waterfall", "bungeecord"], Some("WATERFALL"), true),
+                "bungeecord" => (vec!["bungeecord"], None, true),
+                _ => {
+                    return Err(Error::ResourceNotFound(format!(
+                        "Plugins are not supported for {} servers",
+                        server.jar_name
+                    )))
+                }
+            };
+        // Proxy versions (3.3.0, ...) are not game versions
+        let game_version = (!proxy && server.version != "Unknown").then(|| server.version.clone());
+        Ok(Self {
+            loaders,
+            hangar,
+            game_version,
+        })
+    }
+}
+
+pub struct PluginRelease {
+    pub source: PluginSource,
+    pub project_id: String,
+    // What users type, Modrinth ids are random strings
+    pub slug: String,
+    pub name: String,
+    pub version: String,
+    pub file_name: String,
+    pub url: String,
+    pub hash: String,
+    pub dependencies: Vec<PluginDependency>,
+}
+
+pub struct PluginDependency {
+    pub source: PluginSource,
+    pub project: Option<String>,
+    pub version: Option<String>,
+    pub name: String,
+}
+
+// Base URLs of the plugin repositories, swappable for tests
+pub struct PluginApis {
+    pub modrinth: String,
+    pub hangar: String,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct ModrinthProject {
+    id: String,
+    slug: String,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct ModrinthVersion {
+    project_id: String,
+    version_number: String,
+    files: Vec<ModrinthFile>,
+    dependencies: Vec<ModrinthDependency>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthFile {
+    hashes: HashMap<String, String>,
+    url: String,
+    filename: String,
+    primary: bool,
+}
+
+#[derive(Deserialize)]
+struct ModrinthDependency {
+    version_id: Option<String>,
+    project_id: Option<String>,
+    dependency_type: String,
+}
+
+#[derive(Deserialize)]
+struct ModrinthSearch {
+    hits: Vec<ModrinthHit>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthHit {
+    slug: String,
+}
+
+#[derive(Deserialize)]
+struct HangarProject {
+    name: String,
+    namespace: HangarNamespace,
+}
+
+#[derive(Deserialize)]
+struct HangarNamespace {
+    slug: String,
+}
+
+#[derive(Deserialize)]
+struct HangarPage<T> {
+    result: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarVersion {
+    name: String,
+    downloads: HashMap<String, HangarDownload>,
+    #[serde(default)]
+    plugin_dependencies: HashMap<String, Vec<HangarDependency>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarDownload {
+    file_info: Option<HangarFileInfo>,
+    external_url: Option<String>,
+    download_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarFileInfo {
+    name: String,
+    sha256_hash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarDependency {
+    name: String,
+    required: bool,
+    external_url: Option<String>,
+}
+
+// Turns a 404 into `None` so a missing project can fall through to the next source
+fn found(response: Response) -> Result<Option<Response>, Error> {
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    Ok(Some(response.error_for_status()?))
+}
+
+impl PluginApis {
+    pub fn new(modrinth: &str, hangar: &str) -> Result<Self, Error> {
+        Ok(Self {
+            modrinth: modrinth.trim_end_matches('/').to_string(),
+            hangar: hangar.trim_end_matches('/').to_string(),
+            client: http_client()?,
+        })
+    }
+
+    pub fn public() -> Result<Self, Error> {
+        Self::new(
+            "https://api.modrinth.com/v2",
+            "https://hangar.papermc.io/api/v1",
+        )
+    }
+
+    /// Finds the newest release of `project` (or the given version) that the platform can load.
+    pub fn find(
+        &self,
+        project: &str,
+        version: Option<&str>,
+        source: Option<PluginSource>,
+        platform: &Platform,
+    ) -> Result<PluginRelease, Error> {
+        let sources = match source {
+            Some(source) => vec![source],
+            None => vec![PluginSource::Modrinth, PluginSource::Hangar],
+        };
+        for source in sources {
+            let release = match source {
+                PluginSource::Modrinth => self.modrinth_release(project, version, platform)?,
+                PluginSource::Hangar => self.hangar_release(project, version, platform)?,
+            };
+            if let Some(release) = release {
+                return Ok(release);
+            }
+        }
+        let suggestions = self.search(project).unwrap_or_default();
+        if suggestions.is_empty() {
+            return Err(Error::ResourceNotFound(format!(
+                "No compatible release of {project} found"
+            )));
+        }
+        Err(Error::ResourceNotFound(format!(
+            "No compatible release of {project} found (did you mean {}?)",
+            suggestions.join(", ")
+        )))
+    }
+
+    pub fn search(&self, query: &str) -> Result<Vec<String>, Error> {
+        let mut results = Vec::new();
+        let response = self
+            .client
+            .get(format!("{}/search", self.modrinth))
+            .query(&[
+                ("query", query),
+                ("facets", r#"[["project_type:plugin"]]"#),
+                ("limit", "5"),
+            ])
+            .send()?;
+        if let Some(response) = found(response)? {
+            let search: ModrinthSearch = response.json()?;
+            results.extend(
+                search
+                    .hits
+                    .into_iter()
+                    .map(|h| format!("{} (modrinth)", h.slug)),
+            );
+        }
+        let response = self
+            .client
+            .get(format!("{}/projects", self.hangar))
+            .query(&[("q", query), ("limit", "5")])
+            .send()?;
+        if let Some(response) = found(response)? {
+            let search: HangarPage<HangarProject> = response.json()?;
+            results.extend(
+                search
+                    .result
+                    .into_iter()
+                    .map(|p| format!("{} (hangar)", p.namespace.slug)),
+            );
+        }
+        Ok(results)
+    }
+
+    fn modrinth_release(
+        &self,
+        project: &str,
+        version: Option<&str>,
+        platform: &Platform,
+    ) -> Result<Option<PluginRelease>, Error> {
+        let response = self
+            .client
+            .get(format!("{}/project/{project}", self.modrinth))
+            .send()?;
+        let Some(response) = found(response)? else {
+            return Ok(None);
+        };
+        let project: ModrinthProject = response.json()?;
+        let version = if let Some(version) = version {
+            let response = self
+                .client
+                .get(format!(
+                    "{}/project/{}/version/{version}",
+                    self.modrinth, project.id
+                ))
+                .send()?;
+            let Some(response) = found(response)? else {
+                return Ok(None);
+            };
+            response.json::<ModrinthVersion>()?
+        } else {
+            let mut query = vec![("loaders", serde_json::to_string(&platform.loaders)?)];
+            if let Some(game_version) = &platform.game_version {
+                query.push(("game_versions", serde_json::to_string(&[game_version])?));
+            }
+            let response = self
+                .client
+                .get(format!("{}/project/{}/version", self.modrinth, project.id))
+                .query(&query)
+                .send()?
+                .error_for_status()?;
+            let versions: Vec<ModrinthVersion> = response.json()?;
+            // Modrinth lists the newest version first
+            let Some(version) = versions.into_iter().next() else {
+                return Ok(None);
+            };
+            version
+        };
+        self.modrinth_to_release(&project, &version).map(Some)
+    }
+
+    fn modrinth_to_release(
+        &self,
+        project: &ModrinthProject,
+        version: &ModrinthVersion,
+    ) -> Result<PluginRelease, Error> {
+        let file = version
+            .files
+            .iter()
+            .find(|f| f.primary)
+            .or(version.files.first())
+            .ok_or(Error::ResourceNotFound(format!(
+                "{} {} has no files",
+                project.title, version.version_number
+            )))?;
+        let (algorithm, hash) = if let Some(hash) = file.hashes.get("sha512") {
+            ("sha512", hash)
+        } else {
+            (
+                "sha1",
+                file.hashes
+                    .get("sha1")
+                    .ok_or(Error::ResourceNotFound(format!(
+                        "{} has no file hash",
+                        file.filename
+                    )))?,
+            )
+        };
+        let mut dependencies = Vec::new();
+        for dependency in &version.dependencies {
+            if dependency.dependency_type != "required" {
+                continue;
+            }
+            let project_id = match (&dependency.project_id, &dependency.version_id) {
+                (Some(project_id), _) => project_id.clone(),
+                (None, Some(version_id)) => {
+                    self.client
+                        .get(format!("{}/version/{version_id}", self.modrinth))
+                        .send()?
+                        .error_for_status()?
+                        .json::<ModrinthVersion>()?
+                        .project_id
+                }
+                (None, None) => continue,
+            };
+            dependencies.push(PluginDependency {
+                source: PluginSource::Modrinth,
+                name: project_id.clone(),
+                project: Some(project_id),
+                version: dependency.version_id.clone(),
+            });
+        }
+        Ok(PluginRelease {
+            source: PluginSource::Modrinth,
+            project_id: project.id.clone(),
+            slug: project.slug.clone(),
+            name: project.title.clone(),
+            version: version.version_number.clone(),
+            file_name: file.filename.clone(),
+            url: file.url.clone(),
+            hash: format!("{algorithm}:{hash}"),
+            dependencies,
+        })
+    }
+
+    fn hangar_release(
+        &self,
+        project: &str,
+        version: Option<&str>,
+        platform: &Platform,
+    ) -> Result<Option<PluginRelease>, Error> {
+        let Some(hangar_platform) = platform.hangar else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .get(format!("{}/projects/{project}", self.hangar))
+            .send()?;
+        let Some(response) = found(response)? else {
+            return Ok(None);
+        };
+        let project: HangarProject = response.json()?;
+        let slug = &project.namespace.slug;
+        let version = if let Some(version) = version {
+            let response = self
+                .client
+                .get(format!(
+                    "{}/projects/{slug}/versions/{version}",
+                    self.hangar
+                ))
+                .send()?;
+            let Some(response) = found(response)? else {
+                return Ok(None);
+            };
+            response.json::<HangarVersion>()?
+        } else {
+            let mut query = vec![("platform", hangar_platform.to_string())];
+            if let Some(game_version) = &platform.game_version {
+                query.push(("platformVersion", game_version.clone()));
+            }
+            let response = self
+                .client
+                .get(format!("{}/projects/{slug}/versions", self.hangar))
+                .query(&query)
+                .send()?
+                .error_for_status()?;
+            let page: HangarPage<HangarVersion> = response.json()?;
+            let Some(version) = page.result.into_iter().next() else {
+                return Ok(None);
+            };
+            version
+        };
+        let Some(download) = version.downloads.get(hangar_platform) else {
+            return Ok(None);
+        };
+        let (Some(file_info), Some(url)) = (&download.file_info, &download.download_url) else {
+            return Err(Error::ResourceNotFound(format!(
+                "{} {} is hosted externally ({}) and cannot be verified",
+                project.name,
+                version.name,
+                download.external_url.as_deref().unwrap_or("unknown url")
+            )));
+        };
+        let dependencies = version
+            .plugin_dependencies
+            .get(hangar_platform)
+            .map(|dependencies| {
+                dependencies
+                    .iter()
+                    .filter(|d| d.required)
+                    .map(|d| PluginDependency {
+                        source: PluginSource::Hangar,
+                        // Dependencies only hosted elsewhere can't be installed from Hangar
+                        project: d.external_url.is_none().then(|| d.name.clone()),
+                        version: None,
+                        name: d.name.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Some(PluginRelease {
+            source: PluginSource::Hangar,
+            project_id: slug.clone(),
+            slug: slug.clone(),
+            name: project.name.clone(),
+            version: version.name.clone(),
+            file_name: file_info.name.clone(),
+            url: url.clone(),
+            hash: format!("sha256:{}", file_info.sha256_hash),
+            dependencies,
+        }))
+    }
+
+    /// Looks up jars on Modrinth by their sha512 hash. Hangar has no such lookup, so
+    /// plugins from Hangar are only known through plugins.lock.
+    pub fn identify(&self, hashes: &[String]) -> Result<HashMap<String, (String, String)>, Error> {
+        let hashes: Vec<&str> = hashes
+            .iter()
+            .map(|h| h.trim_start_matches("sha512:"))
+            .collect();
+        let response = self
+            .client
+            .post(format!("{}/version_files", self.modrinth))
+            .json(&serde_json::json!({ "hashes": hashes, "algorithm": "sha512" }))
+            .send()?;
+        let Some(response) = found(response)? else {
+            return Ok(HashMap::new());
+        };
+        let versions: HashMap<String, ModrinthVersion> = response.json()?;
+        Ok(versions
+            .into_iter()
+            .map(|(hash, v)| (format!("sha512:{hash}"), (v.project_id, v.version_number)))
+            .collect())
+    }
+
+    // Downloads a release and checks it against the hash published by its source
+    pub fn download(&self, release: &PluginRelease) -> Result<Vec<u8>, Error> {
+        println!(
+            "🗂️  Downloading {} from {}...",
+            colorize(&release.file_name, Color::Green),
+            colorize(&release.url, Color::LightPurple)
+        );
+        let bytes = self
+            .client
+            .get(&release.url)
+            .send()?
+            .error_for_status()?
+            .bytes()?
+            .to_vec();
+        verify_hash(&bytes, &release.hash)?;
+        Ok(bytes)
+    }
+}
+
+// Parses `slug` or `slug@version`
+pub fn parse_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('@') {
+        Some((project, version)) if !version.is_empty() => (project, Some(version)),
+        Some((project, _)) => (project, None),
+        None => (spec, None),
+    }
+}
+
+/// Installs a plugin and its required dependencies into the server's plugins folder
+/// and records them in plugins.lock.
+pub fn install(
+    server: &Server,
+    spec: &str,
+    source: Option<PluginSource>,
+    apis: &PluginApis,
+) -> Result<Vec<PluginRelease>, Error> {
+    let platform = Platform::of(server)?;
+    let dir = server.location.join("plugins");
+    std::fs::create_dir_all(&dir)?;
+
+    let (plugins, _) = plugins::inspect(server);
+    let mut installed: Vec<String> = plugins
+        .iter()
+        .flat_map(|p| [Some(&p.name), p.id.as_ref()])
+        .flatten()
+        .map(|n| n.to_lowercase())
+        .collect();
+
+    let mut lock = PluginLock::load(server)?;
+    let (project, version) = parse_spec(spec);
+    let mut queue = vec![apis.find(project, version, source, &platform)?];
+    let mut done: Vec<PluginRelease> = Vec::new();
+    while let Some(release) = queue.pop() {
+        if done.iter().any(|r| r.project_id == release.project_id) {
+            continue;
+        }
+        for dependency in &release.dependencies {
+            if installed.contains(&dependency.name.to_lowercase()) {
+                continue;
+            }
+            let Some(project) = &dependency.project else {
+                println!(
+                    "⚠️ {} requires {}, which must be installed manually",
+                    release.name, dependency.name
+                );
+                continue;
+            };
+            let dependency = apis.find(
+                project,
+                dependency.version.as_deref(),
+                Some(dependency.source),
+                &platform,
+            )?;
+            if installed.contains(&dependency.name.to_lowercase()) {
+                continue;
+            }
+            println!(
+                "📝 {} requires {}, installing it too",
+                release.name, dependency.name
+            );
+            queue.push(dependency);
+        }
+
+        let bytes = apis.download(&release)?;
+        let path = dir.join(&release.file_name);
+        let temp = dir.join(format!(".{}.part", release.file_name));
+        std::fs::write(&temp, bytes)?;
+        std::fs::rename(&temp, &path)?;
+        // A managed jar of another version of the same plugin is replaced
+        if let Some(previous) = lock.get(&release.project_id) {
+            if previous.file_name != release.file_name {
+                let _ = std::fs::remove_file(dir.join(&previous.file_name));
+            }
+        }
+        lock.upsert(LockedPlugin::from_release(&release));
+        lock.save(server)?;
+        println!(
+            "📝 Installed {} {} ({})",
+            colorize(&release.name, Color::Gold),
+            colorize(&release.version, Color::Yellow),
+            release.source
+        );
+        installed.push(release.name.to_lowercase());
+        installed.push(release.project_id.to_lowercase());
+        installed.push(release.slug.to_lowercase());
+        done.push(release);
+    }
+    Ok(done)
+}
@@ -0,0 +1,188 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::plugin_lock::{LockedPlugin, PluginLock};
+use crate::minecraft::plugin_sources::{Platform, PluginApis, PluginRelease, PluginSource};
+use crate::minecraft::plugins::PluginInfo;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, compare_versions, hash_bytes, version_parts, Color};
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+pub const ROLLBACK_DIR: &str = ".rollback";
+
+pub struct PluginUpdate {
+    pub file_name: String,
+    pub name: String,
+    pub source: PluginSource,
+    pub project_id: String,
+    pub current: String,
+    pub latest: PluginRelease,
+}
+
+impl PluginUpdate {
+    pub fn is_outdated(&self) -> bool {
+        if version_parts(&self.current).is_empty() && version_parts(&self.latest.version).is_empty()
+        {
+            return self.current != self.latest.version;
+        }
+        compare_versions(&self.current, &self.latest.version) == Ordering::Less
+    }
+
+    // Matches a plugin by name, project or file name
+    pub fn matches(&self, query: &str) -> bool {
+        [&self.name, &self.project_id, &self.file_name]
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(query))
+    }
+}
+
+/// Finds every plugin with a known source, either from plugins.lock or by looking up
+/// the jar's hash, along with the latest release the server can load.
+pub fn check(server: &Server, apis: &PluginApis) -> Result<Vec<PluginUpdate>, Error> {
+    let platform = Platform::of(server)?;
+    let dir = server.location.join("plugins");
+    let lock = PluginLock::load(server)?;
+
+    let mut known: Vec<(String, LockedPlugin)> = Vec::new();
+    let mut unknown: Vec<(String, String)> = Vec::new();
+    for file in server.plugins() {
+        let file = file.to_string_lossy().to_string();
+        if let Some(locked) = lock.plugins.iter().find(|p| p.file_name == file) {
+            known.push((file, locked.clone()));
+            continue;
+        }
+        let hash = hash_bytes("sha512", &std::fs::read(dir.join(&file))?)?;
+        unknown.push((file, hash));
+    }
+
+    if !unknown.is_empty() {
+        let hashes: Vec<String> = unknown.iter().map(|(_, h)| h.clone()).collect();
+        let identified = apis.identify(&hashes)?;
+        for (file, hash) in unknown {
+            let Some((project_id, version)) = identified.get(&hash) else {
+                println!("⚠️ {file} has no known source, skipping");
+                continue;
+            };
+            let name =
+                PluginInfo::read(&dir.join(&file)).map_or_else(|_| file.clone(), |info| info.name);
+            known.push((
+                file.clone(),
+                LockedPlugin {
+                    source: PluginSource::Modrinth,
+                    project_id: project_id.clone(),
+                    slug: None,
+                    name,
+                    version: version.clone(),
+                    file_name: file,
+                    hash,
+                },
+            ));
+        }
+    }
+
+    let mut updates = Vec::new();
+    for (file_name, plugin) in known {
+        let latest = match apis.find(&plugin.project_id, None, Some(plugin.source), &platform) {
+            Ok(latest) => latest,
+            Err(e) => {
+                println!("⚠️ Could not check {}: {e}", plugin.name);
+                continue;
+            }
+        };
+        updates.push(PluginUpdate {
+            file_name,
+            name: plugin.name,
+            source: plugin.source,
+            project_id: plugin.project_id,
+            current: plugin.version,
+            latest,
+        });
+    }
+    updates.sort_by_key(|u| u.name.to_lowercase());
+    Ok(updates)
+}
+
+pub fn print_table(updates: &[PluginUpdate]) {
+    let width = |f: fn(&PluginUpdate) -> &str, header: &str| {
+        updates
+            .iter()
+            .map(|u| f(u).chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let name_width = width(|u| &u.name, "Plugin");
+    let current_width = width(|u| &u.current, "Current");
+    let latest_width = width(|u| &u.latest.version, "Latest");
+    println!(
+        "  {:name_width$}  {:current_width$}  {:latest_width$}  Source",
+        "Plugin", "Current", "Latest"
+    );
+    for update in updates {
+        let latest = format!("{:latest_width$}", update.latest.version);
+        let latest = if update.is_outdated() {
+            colorize(&latest, Color::Yellow)
+        } else {
+            colorize(&latest, Color::Green)
+        };
+        println!(
+            "  {:name_width$}  {:current_width$}  {latest}  {}",
+            update.name, update.current, update.source
+        );
+    }
+}
+
+/// Replaces the jar of each update with its latest release. The new jar is fully
+/// downloaded and verified and in place before the old one is moved to
+/// `plugins/.rollback/<time>/`, so the plugin is never missing.
+pub fn upgrade(
+    server: &Server,
+    updates: &[&PluginUpdate],
+    apis: &PluginApis,
+) -> Result<PathBuf, Error> {
+    let dir = server.location.join("plugins");
+    let rollback = dir
+        .join(ROLLBACK_DIR)
+        .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+    let mut lock = PluginLock::load(server)?;
+
+    for update in updates {
+        let bytes = apis.download(&update.latest)?;
+        let old = dir.join(&update.file_name);
+        let new = dir.join(&update.latest.file_name);
+        let temp = dir.join(format!(".{}.part", update.latest.file_name));
+        std::fs::write(&temp, bytes)?;
+
+        std::fs::create_dir_all(&rollback)?;
+        // Copied rather than moved, the new jar may replace it under the same name
+        if let Err(e) = std::fs::copy(&old, rollback.join(&update.file_name)) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        if let Err(e) = std::fs::rename(&temp, &new) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        if new != old {
+            std::fs::remove_file(&old)?;
+        }
+
+        lock.upsert(LockedPlugin::from_release(&update.latest));
+        lock.save(server)?;
+        println!(
+            "📦 Upgraded {} {} ➥ {}",
+            colorize(&update.name, Color::Gold),
+            update.current,
+            colorize(&update.latest.version, Color::Green)
+        );
+    }
+    Ok(rollback)
+}
@@ -0,0 +1,468 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, compare_versions, Color};
+use serde_yaml::Value;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// Descriptor files checked in each jar, in order of preference
+const DESCRIPTORS: [&str; 5] = [
+    "paper-plugin.yml",
+    "plugin.yml",
+    "bungee.yml",
+    "velocity-plugin.json",
+    "fabric.mod.json",
+];
+
+#[derive(Clone, Debug, Default)]
+pub struct PluginInfo {
+    pub file: String,
+    pub descriptor: String,
+    pub id: Option<String>,
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub api_version: Option<String>,
+    pub depend: Vec<String>,
+    pub softdepend: Vec<String>,
+    pub main: Option<String>,
+}
+
+pub enum PluginIssue {
+    Unreadable {
+        file: String,
+        reason: String,
+    },
+    MissingDependency {
+        plugin: String,
+        dependency: String,
+    },
+    Duplicate {
+        name: String,
+        files: Vec<String>,
+    },
+    ApiTooNew {
+        plugin: String,
+        api_version: String,
+        server_version: String,
+    },
+}
+
+impl PluginInfo {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        for descriptor in DESCRIPTORS {
+            let Ok(mut entry) = archive.by_name(descriptor) else {
+                continue;
+            };
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            // JSON is valid YAML, so every descriptor goes through the same parser
+            let value: Value = serde_yaml::from_str(&contents)?;
+            let mut info = match descriptor {
+                "velocity-plugin.json" => Self::from_velocity(&value),
+                "fabric.mod.json" => Self::from_fabric(&value),
+                "paper-plugin.yml" => Self::from_paper(&value),
+                _ => Self::from_bukkit(&value),
+            };
+            if !descriptor.ends_with("json") {
+                // YAML reads `version: 1.10` as the float 1.1, so keep the text as written
+                if let Some(version) = raw_scalar(&contents, "version") {
+                    info.version = version;
+                }
+                if let Some(api_version) = raw_scalar(&contents, "api-version") {
+                    info.api_version = Some(api_version);
+                }
+            }
+            info.file = file_name;
+            info.descriptor = descriptor.to_string();
+            return Ok(info);
+        }
+        Err(Error::ResourceNotFound(format!(
+            "No plugin descriptor found in {file_name}"
+        )))
+    }
+
+    // plugin.yml and bungee.yml
+    fn from_bukkit(value: &Value) -> Self {
+        let mut authors = list_field(value, "authors");
+        if let Some(author) = str_field(value, "author") {
+            authors.insert(0, author);
+        }
+        let mut depend = list_field(value, "depend");
+        depend.extend(list_field(value, "depends"));
+        let mut softdepend = list_field(value, "softdepend");
+        softdepend.extend(list_field(value, "softDepends"));
+        Self {
+            name: str_field(value, "name").unwrap_or_default(),
+            version: str_field(value, "version").unwrap_or_default(),
+            authors,
+            api_version: str_field(value, "api-version"),
+            depend,
+            softdepend,
+            main: str_field(value, "main"),
+            ..Self::default()
+        }
+    }
+
+    fn from_paper(value: &Value) -> Self {
+        let mut info = Self::from_bukkit(value);
+        info.depend.clear();
+        info.softdepend.clear();
+        match value.get("dependencies") {
+            // Old format: a list of { name, required }
+            Some(Value::Sequence(dependencies)) => {
+                for dependency in dependencies {
+                    let Some(name) = str_field(dependency, "name") else {
+                        continue;
+                    };
+                    if bool_field(dependency, "required").unwrap_or(true) {
+                        info.depend.push(name);
+                    } else {
+                        info.softdepend.push(name);
+                    }
+                }
+            }
+            // New format: server/bootstrap maps of name -> { load, required }
+            Some(Value::Mapping(groups)) => {
+                for dependencies in groups.values() {
+                    let Value::Mapping(dependencies) = dependencies else {
+                        continue;
+                    };
+                    for (name, dependency) in dependencies {
+                        let Some(name) = scalar_to_string(name) else {
+                            continue;
+                        };
+                        if bool_field(dependency, "required").unwrap_or(true) {
+                            info.depend.push(name);
+                        } else {
+                            info.softdepend.push(name);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        info
+    }
+
+    fn from_velocity(value: &Value) -> Self {
+        let id = str_field(value, "id");
+        let mut depend = Vec::new();
+        let mut softdepend = Vec::new();
+        if let Some(Value::Sequence(dependencies)) = value.get("dependencies") {
+            for dependency in dependencies {
+                let Some(id) = str_field(dependency, "id") else {
+                    continue;
+                };
+                if bool_field(dependency, "optional").unwrap_or(false) {
+                    softdepend.push(id);
+                } else {
+                    depend.push(id);
+                }
+            }
+        }
+        Self {
+            name: str_field(value, "name")
+                .or_else(|| id.clone())
+                .unwrap_or_default(),
+            id,
+            version: str_field(value, "version").unwrap_or_default(),
+            authors: list_field(value, "authors"),
+            depend,
+            softdepend,
+            main: str_field(value, "main"),
+            ..Self::default()
+        }
+    }
+
+    fn from_fabric(value: &Value) -> Self {
+        let id = str_field(value, "id");
+        let authors = match value.get("authors") {
+            Some(Value::Sequence(authors)) => authors
+                .iter()
+                .filter_map(|a| scalar_to_string(a).or_else(|| str_field(a, "name")))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let keys = |field: &str| -> Vec<String> {
+            match value.get(field) {
+                Some(Value::Mapping(map)) => map.keys().filter_map(scalar_to_string).collect(),
+                _ => Vec::new(),
+            }
+        };
+        let mut softdepend = keys("recommends");
+        softdepend.extend(keys("suggests"));
+        let main = value
+            .get("entrypoints")
+            .and_then(|e| e.get("main"))
+            .and_then(|m| match m {
+                Value::Sequence(entries) => entries.first().and_then(scalar_to_string),
+                _ => None,
+            });
+        Self {
+            name: str_field(value, "name")
+                .or_else(|| id.clone())
+                .unwrap_or_default(),
+            id,
+            version: str_field(value, "version").unwrap_or_default(),
+            authors,
+            depend: keys("depends"),
+            softdepend,
+            main,
+            ..Self::default()
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "  📦 {} {} ({})",
+            colorize(&self.name, Color::Gold),
+            colorize(&format!("v{}", self.version), Color::Yellow),
+            self.file
+        );
+        if !self.authors.is_empty() {
+            println!("      ➥ Authors: {}", self.authors.join(", "));
+        }
+        if let Some(api_version) = &self.api_version {
+            println!("      ➥ API version: {api_version}");
+        }
+        if !self.depend.is_empty() {
+            println!("      ➥ Depends: {}", self.depend.join(", "));
+        }
+        if !self.softdepend.is_empty() {
+            println!("      ➥ Soft depends: {}", self.softdepend.join(", "));
+        }
+        if let Some(main) = &self.main {
+            println!("      ➥ Main: {main}");
+        }
+    }
+}
+
+impl PluginIssue {
+    pub fn print(&self) {
+        let message = match self {
+            PluginIssue::Unreadable { file, reason } => format!("{file} could not be read: {reason}"),
+            PluginIssue::MissingDependency { plugin, dependency } => {
+                format!("{plugin} depends on {dependency}, which is not installed")
+            }
+            PluginIssue::Duplicate { name, files } => {
+                format!("{name} is installed more than once ({})", files.join(", "))
+            }
+            PluginIssue::ApiTooNew {
+                plugin,
+                api_version,
+                server_version,
+            } => format!(
+                "{plugin} targets API {api_version}, which is newer than the server ({server_version})"
+            ),
+        };
+        println!("  ⚠️ {}", colorize(&message, Color::Yellow));
+    }
+}
+
+// Folder inside plugins/ that the server doesn't load jars from
+pub const DISABLED_DIR: &str = ".disabled";
+
+/// Finds a jar in `dir` by file name (with or without `.jar`) or by the plugin name in
+/// its descriptor.
+pub fn find_jar(dir: &Path, query: &str) -> Result<String, Error> {
+    let query = query.to_lowercase();
+    let mut matches = Vec::new();
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().unwrap_or_default() != "jar" {
+                continue;
+            }
+            let file = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let stem = file.trim_end_matches(".jar").to_lowercase();
+            let by_name = PluginInfo::read(&path).is_ok_and(|info| {
+                info.name.to_lowercase() == query
+                    || info.id.is_some_and(|id| id.to_lowercase() == query)
+            });
+            if file.to_lowercase() == query || stem == query || by_name {
+                matches.push(file);
+            }
+        }
+    }
+    match matches.len() {
+        0 => Err(Error::ResourceNotFound(format!("Plugin {query} not found"))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::ResourceNotFound(format!(
+            "{query} matches several jars ({}), use the file name instead",
+            matches.join(", ")
+        ))),
+    }
+}
+
+// Moves a plugin into plugins/.disabled/ and returns its file name
+pub fn disable(server: &Server, query: &str) -> Result<String, Error> {
+    let dir = server.location.join("plugins");
+    let file = find_jar(&dir, query)?;
+    let disabled = dir.join(DISABLED_DIR);
+    std::fs::create_dir_all(&disabled)?;
+    std::fs::rename(dir.join(&file), disabled.join(&file))?;
+    Ok(file)
+}
+
+// Moves a plugin from plugins/.disabled/ back into plugins/ and returns its file name
+pub fn enable(server: &Server, query: &str) -> Result<String, Error> {
+    let dir = server.location.join("plugins");
+    let disabled = dir.join(DISABLED_DIR);
+    let file = find_jar(&disabled, query)?;
+    if dir.join(&file).exists() {
+        return Err(Error::ResourceNotFound(format!(
+            "{file} already exists in plugins, remove it first"
+        )));
+    }
+    std::fs::rename(disabled.join(&file), dir.join(&file))?;
+    Ok(file)
+}
+
+// Deletes a plugin jar, enabled or disabled, and returns its file name
+pub fn remove(server: &Server, query: &str) -> Result<String, Error> {
+    let dir = server.location.join("plugins");
+    let file = if let Ok(file) = find_jar(&dir, query) {
+        file
+    } else {
+        let file = find_jar(&dir.join(DISABLED_DIR), query)?;
+        format!("{DISABLED_DIR}/{file}")
+    };
+    server.remove_plugin(&file)?;
+    Ok(file
+        .trim_start_matches(&format!("{DISABLED_DIR}/"))
+        .to_string())
+}
+
+// Reads the metadata of every jar in the server's plugins folder
+pub fn inspect(server: &Server) -> (Vec<PluginInfo>, Vec<PluginIssue>) {
+    let dir = server.location.join("plugins");
+    let mut plugins = Vec::new();
+    let mut issues = Vec::new();
+    for file in server.plugins() {
+        match PluginInfo::read(&dir.join(&file)) {
+            Ok(info) => plugins.push(info),
+            Err(e) => issues.push(PluginIssue::Unreadable {
+                file: file.to_string_lossy().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+    plugins.sort_by_key(|p| p.name.to_lowercase());
+    issues.extend(find_issues(&plugins, &server.version));
+    (plugins, issues)
+}
+
+pub fn find_issues(plugins: &[PluginInfo], server_version: &str) -> Vec<PluginIssue> {
+    let mut issues = Vec::new();
+    let installed: Vec<String> = plugins
+        .iter()
+        .flat_map(|p| [Some(&p.name), p.id.as_ref()])
+        .flatten()
+        .map(|n| n.to_lowercase())
+        .collect();
+
+    for plugin in plugins {
+        for dependency in &plugin.depend {
+            // Fabric mods depend on the game and loader themselves
+            if ["minecraft", "java", "fabricloader", "fabric", "velocity"]
+                .contains(&dependency.to_lowercase().as_str())
+            {
+                continue;
+            }
+            if !installed.contains(&dependency.to_lowercase()) {
+                issues.push(PluginIssue::MissingDependency {
+                    plugin: plugin.name.clone(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+        if let Some(api_version) = &plugin.api_version {
+            if server_version != "Unknown"
+                && compare_versions(api_version, server_version) == Ordering::Greater
+            {
+                issues.push(PluginIssue::ApiTooNew {
+                    plugin: plugin.name.clone(),
+                    api_version: api_version.clone(),
+                    server_version: server_version.to_string(),
+                });
+            }
+        }
+    }
+
+    let mut seen: Vec<String> = Vec::new();
+    for plugin in plugins {
+        let name = plugin.name.to_lowercase();
+        if seen.contains(&name) {
+            continue;
+        }
+        seen.push(name.clone());
+        let files: Vec<String> = plugins
+            .iter()
+            .filter(|p| p.name.to_lowercase() == name)
+            .map(|p| format!("{} v{}", p.file, p.version))
+            .collect();
+        if files.len() > 1 {
+            issues.push(PluginIssue::Duplicate {
+                name: plugin.name.clone(),
+                files,
+            });
+        }
+    }
+    issues
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// Top level `key: value` as written in the file, without quotes
+fn raw_scalar(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?;
+        let value = value.split(" #").next()?.trim().trim_matches(['"', '\'']);
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(scalar_to_string)
+}
+
+fn bool_field(value: &Value, key: &str) -> Option<bool> {
+    value.get(key).and_then(Value::as_bool)
+}
+
+fn list_field(value: &Value, key: &str) -> Vec<String> {
+    match value.get(key) {
+        Some(Value::Sequence(items)) => items.iter().filter_map(scalar_to_string).collect(),
+        Some(other) => scalar_to_string(other).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
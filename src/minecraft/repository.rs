@@ -0,0 +1,203 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::utils::hash_bytes;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// Chunk sizes for content-defined chunking, the average is about 64 KiB
+pub const MIN_CHUNK: usize = 16 * 1024;
+pub const MAX_CHUNK: usize = 256 * 1024;
+const CHUNK_MASK: u64 = (1 << 16) - 1;
+
+// Random values for the gear rolling hash, generated with splitmix64
+const GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+/// Length of the first chunk of `data`. The cut only depends on the bytes around it,
+/// so an edit early in a file doesn't move the boundaries after it.
+pub fn cut_point(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK {
+        return data.len();
+    }
+    let end = data.len().min(MAX_CHUNK);
+    let mut hash: u64 = 0;
+    for (i, byte) in data.iter().enumerate().take(end).skip(MIN_CHUNK) {
+        hash = (hash << 1).wrapping_add(GEAR[usize::from(*byte)]);
+        if hash & CHUNK_MASK == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
+/// Splits a stream into content-defined chunks.
+pub struct Chunker<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: Read> Chunker<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::with_capacity(MAX_CHUNK),
+            eof: false,
+        }
+    }
+
+    fn fill(&mut self) -> std::io::Result<()> {
+        let wanted = MAX_CHUNK.saturating_sub(self.buffer.len());
+        if !self.eof && wanted > 0 {
+            let read = (&mut self.reader)
+                .take(wanted as u64)
+                .read_to_end(&mut self.buffer)?;
+            self.eof = read < wanted;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Chunker<R> {
+    type Item = std::io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill() {
+            return Some(Err(e));
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let cut = cut_point(&self.buffer);
+        Some(Ok(self.buffer.drain(..cut).collect()))
+    }
+}
+
+/// Chunk store of incremental backups: every chunk is kept once, compressed with zstd,
+/// under `chunks/<first two hex digits>/<sha256>`.
+pub struct Repository {
+    pub root: PathBuf,
+}
+
+impl Repository {
+    pub fn open(root: &Path) -> Result<Self, Error> {
+        std::fs::create_dir_all(root.join("chunks"))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    fn chunk_path(&self, hash: &str) -> PathBuf {
+        let hex = hash.trim_start_matches("sha256:");
+        self.root
+            .join("chunks")
+            .join(hex.get(..2).unwrap_or("00"))
+            .join(hex)
+    }
+
+    /// Stores a chunk unless it is already there. Returns its hash and the bytes added.
+    pub fn store(&self, chunk: &[u8]) -> Result<(String, u64), Error> {
+        let hash = hash_bytes("sha256", chunk)?;
+        let path = self.chunk_path(&hash);
+        if path.is_file() {
+            return Ok((hash, 0));
+        }
+        let compressed = zstd::encode_all(chunk, 3)?;
+        std::fs::create_dir_all(path.parent().unwrap_or(&self.root))?;
+        let temp = path.with_extension("part");
+        std::fs::write(&temp, &compressed)?;
+        std::fs::rename(&temp, &path)?;
+        Ok((hash, compressed.len() as u64))
+    }
+
+    /// Splits a file into chunks and stores them. Returns the chunk hashes and the bytes added.
+    pub fn store_file(&self, path: &Path) -> Result<(Vec<String>, u64), Error> {
+        let mut hashes = Vec::new();
+        let mut added = 0;
+        for chunk in Chunker::new(std::fs::File::open(path)?) {
+            let (hash, size) = self.store(&chunk?)?;
+            hashes.push(hash);
+            added += size;
+        }
+        Ok((hashes, added))
+    }
+
+    /// Reads a chunk back, failing if it is missing or doesn't match its hash.
+    pub fn load(&self, hash: &str) -> Result<Vec<u8>, Error> {
+        let path = self.chunk_path(hash);
+        if !path.is_file() {
+            return Err(Error::ResourceNotFound(format!("Chunk {hash} is missing")));
+        }
+        let chunk = zstd::decode_all(std::fs::File::open(path)?)?;
+        let actual = hash_bytes("sha256", &chunk)?;
+        if actual != hash {
+            return Err(Error::HashMismatch {
+                expected: hash.to_string(),
+                actual,
+            });
+        }
+        Ok(chunk)
+    }
+
+    /// Deletes every chunk that isn't in `referenced`. Returns the bytes freed.
+    pub fn collect_garbage(&self, referenced: &HashSet<&str>) -> Result<u64, Error> {
+        let mut freed = 0;
+        for (chunk, size) in self.garbage(referenced)? {
+            std::fs::remove_file(&chunk)?;
+            freed += size;
+        }
+        Ok(freed)
+    }
+
+    /// Bytes `collect_garbage` would free.
+    pub fn garbage_size(&self, referenced: &HashSet<&str>) -> Result<u64, Error> {
+        Ok(self.garbage(referenced)?.iter().map(|(_, size)| size).sum())
+    }
+
+    // Chunks that aren't in `referenced`, with their size on disk
+    fn garbage(&self, referenced: &HashSet<&str>) -> Result<Vec<(PathBuf, u64)>, Error> {
+        let referenced: HashSet<&str> = referenced
+            .iter()
+            .map(|h| h.trim_start_matches("sha256:"))
+            .collect();
+        let mut garbage = Vec::new();
+        for prefix in std::fs::read_dir(self.root.join("chunks"))? {
+            let prefix = prefix?.path();
+            if !prefix.is_dir() {
+                continue;
+            }
+            for chunk in std::fs::read_dir(&prefix)? {
+                let chunk = chunk?.path();
+                let name = chunk.file_name().unwrap_or_default().to_string_lossy();
+                if !referenced.contains(name.as_ref()) {
+                    let size = chunk.metadata()?.len();
+                    garbage.push((chunk, size));
+                }
+            }
+        }
+        Ok(garbage)
+    }
+}
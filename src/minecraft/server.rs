@@ -9,17 +9,23 @@
 
 use crate::config::Config;
 use crate::error::Error;
+use crate::java::runtime::{self, JavaRequirement, JavaRuntime};
+use crate::minecraft::backup::BackupSettings;
+use crate::minecraft::console;
+use crate::minecraft::jvm::JvmSettings;
+use crate::minecraft::memory::DEFAULT_HEAP;
+use crate::minecraft::migration::Software;
 use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::trash::{Trash, TrashEntry};
 use crate::utils::{canonize, colorize, read_line, Color};
 use serde::{Deserialize, Serialize};
 use std::ffi::OsString;
 use std::fs::read_dir;
-use std::io::{BufRead, BufReader};
 use std::ops::Add;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::thread;
 
+#[allow(clippy::struct_field_names)]
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Server {
     pub server_name: String,
@@ -31,7 +37,31 @@ pub struct Server {
     pub xms: Option<String>,
     pub xmx: Option<String>,
 
+    // Writes eula.txt before every start, so the server never stops to ask
+    #[serde(default)]
+    pub accept_eula: bool,
+
+    // Counted when checking that the heaps of auto-started servers fit in memory
+    #[serde(default)]
+    pub auto_start: bool,
+
+    // Java executable or home to run with, otherwise picked from the installed runtimes
+    #[serde(default)]
+    pub java_path: Option<PathBuf>,
+    // Major Java version to look for instead of the one the Minecraft version needs
+    #[serde(default)]
+    pub java_version: Option<u32>,
+
     pub location: PathBuf,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub backup: BackupSettings,
+
+    #[serde(default)]
+    pub jvm: JvmSettings,
 }
 
 impl Server {
@@ -53,6 +83,13 @@ impl Server {
             gui: true,
             xms: None,
             xmx: None,
+            accept_eula: false,
+            auto_start: false,
+            java_path: None,
+            java_version: None,
+            tags: Vec::new(),
+            backup: BackupSettings::default(),
+            jvm: JvmSettings::default(),
         };
         println!("📝 Saving server info...");
         server.write();
@@ -75,6 +112,22 @@ impl Server {
             self.xmx.as_ref().unwrap_or(&"Default".to_string()),
             Color::Gold,
         );
+        let java = colorize(
+            &match (&self.java_path, self.java_version) {
+                (Some(path), _) => path.display().to_string(),
+                (None, Some(major)) => format!("Java {major}"),
+                (None, None) => "Auto".to_string(),
+            },
+            Color::Gold,
+        );
+        let tags = colorize(
+            &if self.tags.is_empty() {
+                "None".to_string()
+            } else {
+                self.tags.join(", ")
+            },
+            Color::Gold,
+        );
 
         println!(
             "\
@@ -86,6 +139,8 @@ impl Server {
             📦 GUI: {gui}\n\
             📦 Xms: {xms}\n\
             📦 Xmx: {xmx}\n\
+            📦 Java: {java}\n\
+            📦 Tags: {tags}\n\
             ==================="
         );
     }
@@ -99,107 +154,144 @@ impl Server {
             self.location.display()
         );
 
-        // Find jar in dir
-        let mut jar_name = self.jar_name.clone();
-        if &self.version != "Unknown" {
-            jar_name = jar_name.add("-").add(&*self.version);
+        let command_line = self.command_line()?;
+        println!("☕ Using {}", command_line[0]);
+
+        // Proxies have no EULA to accept. Asked before the console starts reading stdin.
+        let needs_eula = Software::of(&self.jar_name) != Software::Proxy;
+        if needs_eula && !self.eula_accepted() {
+            let accepted = accept_eula
+                || self.accept_eula
+                || read_line("🚨 EULA not accepted! Would you like to accept? (y/n)")?
+                    .eq_ignore_ascii_case("y");
+            if !accepted {
+                println!("🚨 EULA not accepted! Exiting...");
+                return Ok(());
+            }
+            self.accept_eula()?;
         }
-        jar_name = jar_name.add(".jar");
 
         // Run jar
-        let mut process = Command::new("java")
+        let mut process = Command::new(&command_line[0])
             .current_dir(&self.location)
-            .arg(format!("-Dname={}", server_info.server_name.trim()))
-            .arg(format!(
-                "-Xms{}",
-                server_info.xms.unwrap_or_else(|| "1G".to_string())
-            ))
-            .arg(format!(
-                "-Xmx{}",
-                server_info.xmx.unwrap_or_else(|| "1G".to_string())
-            ))
-            .args([
-                "-XX:+UseG1GC",
-                "-XX:+ParallelRefProcEnabled",
-                "-XX:MaxGCPauseMillis=200",
-            ])
-            .args([
-                "-XX:+UnlockExperimentalVMOptions",
-                "-XX:+DisableExplicitGC",
-                "-XX:+AlwaysPreTouch",
-                "-XX:G1NewSizePercent=30",
-                "-XX:G1MaxNewSizePercent=40",
-                "-XX:G1HeapRegionSize=8M",
-                "-XX:G1ReservePercent=20",
-                "-XX:G1HeapWastePercent=5",
-                "-XX:G1MixedGCCountTarget=4",
-                "-XX:InitiatingHeapOccupancyPercent=15",
-                "-XX:G1MixedGCLiveThresholdPercent=90",
-                "-XX:G1RSetUpdatingPauseTimePercent=5",
-                "-XX:SurvivorRatio=32",
-            ])
-            .args([
-                "-XX:+PerfDisableSharedMem",
-                "-XX:MaxTenuringThreshold=1",
-                "-Dusing.aikars.flags=https://mcflags.emc.gs",
-                "-Daikars.new.flags=true",
-            ])
-            .arg("-jar")
-            .arg(self.location.join(jar_name))
-            .arg(if server_info.gui { "" } else { "--nogui" })
+            .args(&command_line[1..])
+            .stdin(Stdio::piped())
             .stdout(Stdio::inherit())
             .spawn()
             .expect("Failed to start child");
 
-        if let Some(ref mut stdout) = process.stdout {
-            let reader = BufReader::new(stdout);
-
-            for line in reader.lines() {
-                let text = line.unwrap();
-                if text.contains("You need to agree to the EULA in order to run the server. Go to eula.txt for more info.") {
-                    if accept_eula {
-                        self.accept_eula();
-                        let mut server_clone = self.clone();
-                        let server_copy = thread::spawn(move || server_clone.run(false)); // Create
-                        // thread so we can get "out" of the loop
-                        server_copy.join().unwrap()?;
-                        break;
-                    }
-                    let input = read_line("🚨 EULA not accepted! Would you like to accept? (y/n)")?.to_lowercase();
-                    if input == "y" {
-                        println!("🛑 Stopping server");
-                        process.kill().expect("Failed to kill child");
-                        self.accept_eula();
-                        let mut server_clone = self.clone();
-                        let server_copy = thread::spawn(move || server_clone.run(false)); // Create
-                        // thread so we can get "out" of the loop
-                        server_copy.join().unwrap()?;
-                        break;
-                    }
-
-                    println!("🚨 EULA not accepted! Exiting...");
-                    break;
-                }
-
-                println!("{text}");
+        // Lets `boxes server backup` and others send commands to the console, and forwards
+        // what is typed in this terminal
+        let bridge = match process
+            .stdin
+            .take()
+            .map(|stdin| console::open_bridge(&self.location, stdin))
+        {
+            Some(Ok(bridge)) => Some(bridge),
+            Some(Err(e)) => {
+                let _ = process.kill();
+                let _ = process.wait();
+                return Err(e);
             }
-        }
+            None => None,
+        };
+        console::forward_stdin();
 
         process.wait()?;
+        if let Some(bridge) = bridge {
+            let _ = std::fs::remove_file(bridge);
+        }
         Ok(())
     }
 
-    pub fn accept_eula(&self) {
+    /// Everything `run` executes, starting with the Java executable.
+    pub fn command_line(&self) -> Result<Vec<String>, Error> {
+        let mut command = vec![self.java_executable()?.to_string_lossy().to_string()];
+        command.push(format!("-Dname={}", self.server_name.trim()));
+        command.push(format!("-Xms{}", self.xms.as_deref().unwrap_or(DEFAULT_HEAP)));
+        command.push(format!("-Xmx{}", self.xmx.as_deref().unwrap_or(DEFAULT_HEAP)));
+        let profile = self.jvm.profile.resolve(&self.jar_name, self.xmx.as_deref());
+        command.extend(profile.flags().into_iter().map(str::to_string));
+        command.extend(self.jvm.jvm_args.iter().cloned());
+        command.push("-jar".to_string());
+        command.push(self.jar_path().to_string_lossy().to_string());
+        // Proxies don't know --nogui
+        if !self.gui && Software::of(&self.jar_name) != Software::Proxy {
+            command.push("--nogui".to_string());
+        }
+        command.extend(self.jvm.args.iter().cloned());
+        Ok(command)
+    }
+
+    /// Java to run the server with: `java_path` when set, otherwise the installed runtime
+    /// matching `java_version` or what the Minecraft version needs, falling back to the
+    /// `java` on PATH when its version fits.
+    pub fn java_executable(&self) -> Result<PathBuf, Error> {
+        if let Some(path) = &self.java_path {
+            if path.is_file() {
+                return Ok(path.clone());
+            }
+            return JavaRuntime::from_home(path)
+                .map(|runtime| runtime.executable())
+                .ok_or(Error::ResourceNotFound(format!(
+                    "No Java runtime at {}",
+                    path.display()
+                )));
+        }
+        let requirement = match self.java_version {
+            Some(major) => Some(JavaRequirement::exactly(major)),
+            None => runtime::required_java(&self.jar_name, &self.version),
+        };
+        match requirement {
+            Some(requirement) => runtime::find(&runtime::discover(), requirement).or_else(|e| {
+                match runtime::path_major() {
+                    Some(major) if requirement.allows(major) => Ok(PathBuf::from("java")),
+                    _ => Err(e),
+                }
+            }),
+            // Nothing to go on, leave it to PATH
+            None => Ok(PathBuf::from("java")),
+        }
+    }
+
+    // The server jar, named `<jar_name>-<version>.jar`
+    pub fn jar_path(&self) -> PathBuf {
+        let mut jar_name = self.jar_name.clone();
+        if &self.version != "Unknown" {
+            jar_name = jar_name.add("-").add(&*self.version);
+        }
+        self.location.join(jar_name.add(".jar"))
+    }
+
+    // Writes eula.txt with eula=true, creating it the way the server would when missing
+    pub fn accept_eula(&self) -> Result<(), Error> {
         println!("📝 Accepting EULA...");
         let eula_path = self.location.join("eula.txt");
-        if !eula_path.exists() {
-            println!("🚨 EULA not found!");
-            return;
-        }
-        let mut eula = std::fs::read_to_string(&eula_path).unwrap();
-        eula = eula.replace("eula=false", "eula=true");
-        std::fs::write(&eula_path, eula).unwrap();
+        let eula = match std::fs::read_to_string(&eula_path) {
+            Ok(eula) => eula.replace("eula=false", "eula=true"),
+            Err(_) => format!(
+                "#By changing the setting below to TRUE you are indicating your agreement to our EULA (https://aka.ms/MinecraftEULA).\n#{}\neula=true\n",
+                chrono::Local::now().format("%a %b %d %H:%M:%S %Z %Y")
+            ),
+        };
+        std::fs::write(&eula_path, eula)?;
         println!("📝 Accepted EULA!");
+        Ok(())
+    }
+
+    /// Keeps the EULA accepted for every start and writes eula.txt before the first one.
+    pub fn remember_eula(&mut self) -> Result<(), Error> {
+        self.accept_eula = true;
+        self.write();
+        if Software::of(&self.jar_name) != Software::Proxy {
+            self.accept_eula()?;
+        }
+        Ok(())
+    }
+
+    pub fn eula_accepted(&self) -> bool {
+        std::fs::read_to_string(self.location.join("eula.txt"))
+            .is_ok_and(|eula| eula.lines().any(|line| line.trim() == "eula=true"))
     }
 
     pub fn from_path(path: &str) -> Result<Self, Error> {
@@ -233,10 +325,11 @@ impl Server {
         Ok(server_info)
     }
 
-    pub fn delete(&self) {
-        println!("📝 Deleting server...");
-        std::fs::remove_dir_all(&self.location).expect("🚨 Failed to delete server!");
-        println!("📝 Deleted server!");
+    pub fn delete(&self) -> Result<TrashEntry, Error> {
+        println!("📝 Moving server to the trash...");
+        let entry = Trash::open()?.put(self)?;
+        println!("📝 Moved server to the trash!");
+        Ok(entry)
     }
 
     pub fn plugins(&self) -> Vec<OsString> {
@@ -254,19 +347,28 @@ impl Server {
         std::fs::write(server_info_path, server_info).unwrap();
     }
 
-    pub fn remove_plugin(&self, plugin: &str) {
+    pub fn remove_plugin(&self, plugin: &str) -> Result<(), Error> {
         let dir = self.location.join("plugins");
         if !dir.exists() {
-            println!("🚨 Plugins directory not found!");
-            return;
+            return Err(Error::ResourceNotFound(
+                "Plugins directory not found".to_string(),
+            ));
         }
         let path = dir.join(plugin);
         if !path.exists() {
-            println!("🚨 Plugin not found!");
-            return;
+            return Err(Error::ResourceNotFound("Plugin not found".to_string()));
         }
-        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(path)?;
         println!("📝 Removed plugin!");
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        match running_jvms() {
+            Some(jvms) => is_running(&jvms, &self.server_name),
+            // JREs ship without jps, the console bridge is only open while the server runs
+            None => console::bridge_is_open(&self.location),
+        }
     }
 
     pub fn optimize(&self, verbose: bool) {
@@ -292,6 +394,27 @@ impl Server {
     }
 }
 
+// Output of `jps -v`, which lists running JVMs with their arguments (None without a JDK)
+pub fn running_jvms() -> Option<String> {
+    Command::new("jps")
+        .arg("-v")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8(output.stdout).unwrap_or_default())
+}
+
+// Servers are started with `-Dname=<server name>` so they can be found in `jps -v`. The
+// name may contain spaces, so it runs up to the next ` -` argument.
+pub fn is_running(jvms: &str, server_name: &str) -> bool {
+    let name = server_name.trim();
+    jvms.lines().any(|line| {
+        line.split("-Dname=").skip(1).any(|value| {
+            let value = value.split(" -").next().unwrap_or_default();
+            value.trim() == name
+        })
+    })
+}
+
 fn get_jars(path: &Path) -> Result<Vec<OsString>, Error> {
     let mut jars = vec![];
     for entry in read_dir(path)? {
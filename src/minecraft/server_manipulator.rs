@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::error::Error;
 use crate::minecraft::server::Server;
 use std::io::Write;
 
@@ -28,6 +29,44 @@ impl ServerManipulator {
         Some(properties)
     }
 
+    pub fn get_property(&self, key: &str) -> Option<String> {
+        let file = std::fs::read_to_string(self.server.location.join("server.properties")).ok()?;
+        file.lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .find(|(k, _)| k.trim() == key)
+            .map(|(_, v)| v.trim().to_string())
+    }
+
+    // Changes one property in place, keeping the order and comments of the file
+    pub fn set_property(&self, key: &str, value: &str) -> Result<(), Error> {
+        let path = self.server.location.join("server.properties");
+        let file = if path.exists() {
+            std::fs::read_to_string(&path)?
+        } else {
+            String::new()
+        };
+        let mut found = false;
+        let mut lines: Vec<String> = file
+            .lines()
+            .map(|line| {
+                let matches = !line.starts_with('#')
+                    && line.split_once('=').is_some_and(|(k, _)| k.trim() == key);
+                if matches {
+                    found = true;
+                    format!("{key}={value}")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        if !found {
+            lines.push(format!("{key}={value}"));
+        }
+        std::fs::write(path, lines.join("\n") + "\n")?;
+        Ok(())
+    }
+
     pub fn save_server_properties(&self, properties: &std::collections::HashMap<String, String>) {
         let mut file =
             std::fs::File::create(self.server.location.join("server.properties")).unwrap();
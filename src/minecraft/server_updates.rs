@@ -0,0 +1,211 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::backup::{self, BackupManifest};
+use crate::minecraft::jars::{BuildChanges, Jar};
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, compare_versions, Color};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// How far a server's build is behind the newest build of its version.
+#[derive(Serialize, Debug)]
+pub struct BuildStatus {
+    pub server_name: String,
+    pub jar_name: String,
+    pub version: String,
+    pub build: String,
+    pub latest_build: Option<u32>,
+    // Unknown when the server's build isn't a number
+    pub behind: Option<usize>,
+    pub changes: Vec<BuildChanges>,
+}
+
+impl BuildStatus {
+    pub fn is_outdated(&self) -> bool {
+        self.behind.is_some_and(|behind| behind > 0)
+    }
+}
+
+/// Compares the server's build with the builds upstream has for its version, fetching
+/// the changes of every newer build. A changelog that can't be fetched only leaves the
+/// changes empty.
+pub fn check(server: &Server, jar: &Jar) -> Result<BuildStatus, Error> {
+    let builds = jar.get_builds(&server.version)?;
+    let newer: Option<Vec<u32>> = server
+        .build
+        .parse::<u32>()
+        .ok()
+        .map(|current| builds.iter().copied().filter(|b| *b > current).collect());
+    let changes = match &newer {
+        Some(newer) if !newer.is_empty() => {
+            jar.get_changes(&server.version, newer).unwrap_or_else(|e| {
+                println!(
+                    "⚠️ Could not fetch the changes of {} {}: {e}",
+                    server.jar_name, server.version
+                );
+                Vec::new()
+            })
+        }
+        _ => Vec::new(),
+    };
+    Ok(BuildStatus {
+        server_name: server.server_name.clone(),
+        jar_name: server.jar_name.clone(),
+        version: server.version.clone(),
+        build: server.build.clone(),
+        latest_build: builds.iter().max().copied(),
+        behind: newer.map(|newer| newer.len()),
+        changes,
+    })
+}
+
+pub fn print_table(statuses: &[BuildStatus]) {
+    let width = |f: fn(&BuildStatus) -> &str, header: &str| {
+        statuses
+            .iter()
+            .map(|s| f(s).chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let name_width = width(|s| &s.server_name, "Server");
+    let jar_width = width(|s| &s.jar_name, "Jar");
+    let version_width = width(|s| &s.version, "Version");
+    let build_width = width(|s| &s.build, "Build");
+    println!(
+        "  {:name_width$}  {:jar_width$}  {:version_width$}  {:build_width$}  Latest  Behind",
+        "Server", "Jar", "Version", "Build"
+    );
+    for status in statuses {
+        let latest = status
+            .latest_build
+            .map_or_else(|| "?".to_string(), |b| b.to_string());
+        let behind = status
+            .behind
+            .map_or_else(|| "?".to_string(), |b| b.to_string());
+        let behind = if status.is_outdated() {
+            colorize(&behind, Color::Yellow)
+        } else {
+            colorize(&behind, Color::Green)
+        };
+        println!(
+            "  {:name_width$}  {:jar_width$}  {:version_width$}  {:build_width$}  {latest:6}  {behind}",
+            status.server_name, status.jar_name, status.version, status.build
+        );
+    }
+}
+
+pub fn print_changes(status: &BuildStatus) {
+    println!(
+        "📝 Changes since {} build {}:",
+        status.server_name,
+        colorize(&status.build, Color::Gold)
+    );
+    for build in &status.changes {
+        println!(
+            "  🔨 Build {}",
+            colorize(&build.build.to_string(), Color::Gold)
+        );
+        if build.changes.is_empty() {
+            println!("      No changes listed");
+        }
+        for change in &build.changes {
+            println!("      ➥ {change}");
+        }
+    }
+}
+
+/// Version and build to upgrade to. Without a version the server's own is kept;
+/// without a build (or with `latest`) the newest build of that version is used.
+pub fn resolve(
+    jar: &Jar,
+    server: &Server,
+    version: Option<&str>,
+    build: Option<&str>,
+) -> Result<(String, String), Error> {
+    let version = version.unwrap_or(&server.version).to_string();
+    let build = match build {
+        Some(build) if !build.eq_ignore_ascii_case("latest") => build.to_string(),
+        _ => jar
+            .get_latest_build(&version)
+            .ok_or(Error::ResourceNotFound(format!(
+                "No builds found for {} {version}",
+                jar.name
+            )))?
+            .to_string(),
+    };
+    Ok((version, build))
+}
+
+// Worlds opened by a newer version can't be loaded by an older one
+pub fn is_downgrade(server: &Server, version: &str) -> bool {
+    server.version != "Unknown" && compare_versions(version, &server.version) == Ordering::Less
+}
+
+/// Swaps the server jar for another version or build. A backup is taken first, the new
+/// jar replaces the old one only once it's fully downloaded, and `server_box.toml` follows.
+pub fn upgrade(
+    server: &Server,
+    jar: &Jar,
+    version: &str,
+    build: &str,
+) -> Result<(Server, BackupManifest), Error> {
+    if server.is_running() {
+        return Err(Error::ServerRunning(server.server_name.clone()));
+    }
+    println!("🗄️ Taking a backup before upgrading...");
+    let backup = backup::create(server, server.backup.format, server.backup.worlds_only)?;
+    let upgraded = replace_jar(server, jar, version, build)?;
+    Ok((upgraded, backup))
+}
+
+/// Downloads `jar` next to the server, points `server_box.toml` at it and removes the old jar.
+pub fn replace_jar(
+    server: &Server,
+    jar: &Jar,
+    version: &str,
+    build: &str,
+) -> Result<Server, Error> {
+    let staged = stage_jar(server, jar, version, build)?;
+    swap_jar(server, jar, version, build, &staged)
+}
+
+/// Downloads `jar` next to the server under a temporary name, leaving the server untouched.
+pub fn stage_jar(server: &Server, jar: &Jar, version: &str, build: &str) -> Result<PathBuf, Error> {
+    let staged = server
+        .location
+        .join(format!(".{}.staged", jar.file_name(version)));
+    jar.download_to(version, build, &staged)?;
+    Ok(staged)
+}
+
+/// Moves a jar from `stage_jar` in place, points `server_box.toml` at it and removes the old jar.
+pub fn swap_jar(
+    server: &Server,
+    jar: &Jar,
+    version: &str,
+    build: &str,
+    staged: &Path,
+) -> Result<Server, Error> {
+    let old_jar = server.jar_path();
+    let new_jar = server.location.join(jar.file_name(version));
+    std::fs::rename(staged, &new_jar)?;
+    let mut replaced = server.clone();
+    replaced.jar_name.clone_from(&jar.name);
+    replaced.version = version.to_string();
+    replaced.build = build.to_string();
+    replaced.write();
+    if old_jar != new_jar && old_jar.exists() {
+        std::fs::remove_file(&old_jar)?;
+    }
+    Ok(replaced)
+}
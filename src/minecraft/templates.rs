@@ -0,0 +1,242 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::backup::world_dirs;
+use crate::minecraft::console;
+use crate::minecraft::plugin_updates::ROLLBACK_DIR;
+use crate::minecraft::plugins::DISABLED_DIR;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::utils::{colorize, config_dir, copy_dir, walk_files, Color};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// Fields of server_box.toml that belong to one server and are never taken from a template
+const SERVER_FIELDS: [&str; 6] = [
+    "server_name",
+    "jar_name",
+    "version",
+    "build",
+    "location",
+    "java_path",
+];
+
+// Servers sharing a backup directory would prune each other's chunks
+const SERVER_BACKUP_FIELDS: [&str; 1] = ["directory"];
+
+// Properties every instance gets its own value for
+const INSTANCE_PROPERTIES: [&str; 4] = ["server-port", "server-ip", "rcon.port", "query.port"];
+
+// Left out of the skeleton when a template is made from a server
+const GENERATED_FILES: [&str; 8] = [
+    "logs",
+    "cache",
+    "libraries",
+    "versions",
+    "crash-reports",
+    "eula.txt",
+    "server.properties",
+    "server_box.toml",
+];
+
+// template.toml, next to the files/ skeleton in <config dir>/templates/<name>/
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct Template {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    // server_box.toml fields new servers start with (xmx, jvm, backup...)
+    #[serde(default)]
+    pub server: toml::Table,
+    // server.properties values set before the first boot
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Template {
+    pub fn print(&self) {
+        println!(
+            "  📐 {}{} ({} setting(s), {} property override(s))",
+            colorize(&self.name, Color::Gold),
+            self.description
+                .as_ref()
+                .map(|d| format!(": {d}"))
+                .unwrap_or_default(),
+            self.server.len(),
+            self.properties.len()
+        );
+    }
+}
+
+/// Named server templates: a directory skeleton plus default settings and properties.
+pub struct Templates {
+    pub root: PathBuf,
+}
+
+impl Templates {
+    pub fn open() -> Result<Self, Error> {
+        Self::at(&config_dir()?.join("templates"))
+    }
+
+    pub fn at(root: &Path) -> Result<Self, Error> {
+        std::fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    fn dir(&self, name: &str) -> Result<PathBuf, Error> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::InvalidInput(format!(
+                "Invalid template name {name} (letters, digits, - and _ only)"
+            )));
+        }
+        Ok(self.root.join(name))
+    }
+
+    pub fn files(&self, name: &str) -> Result<PathBuf, Error> {
+        Ok(self.dir(name)?.join("files"))
+    }
+
+    /// Templates, sorted by name.
+    pub fn list(&self) -> Result<Vec<Template>, Error> {
+        let mut templates = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path().join("template.toml");
+            if path.is_file() {
+                templates.push(toml::from_str(&std::fs::read_to_string(path)?)?);
+            }
+        }
+        templates.sort_by(|a: &Template, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Template, Error> {
+        let path = self.dir(name)?.join("template.toml");
+        if !path.is_file() {
+            return Err(Error::ResourceNotFound(format!(
+                "Template {name} not found"
+            )));
+        }
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, template: &Template) -> Result<(), Error> {
+        let dir = self.dir(&template.name)?;
+        std::fs::create_dir_all(dir.join("files"))?;
+        std::fs::write(dir.join("template.toml"), toml::to_string(template)?)?;
+        Ok(())
+    }
+
+    /// Makes a template of a server: its settings, its properties (without ports) and its
+    /// files, leaving out worlds, the jar and what the server generates.
+    pub fn create_from(&self, server: &Server, name: &str) -> Result<Template, Error> {
+        let dir = self.dir(name)?;
+        if dir.exists() {
+            return Err(Error::InvalidInput(format!(
+                "Template {name} already exists"
+            )));
+        }
+        let mut settings = toml::Table::try_from(server)?;
+        settings.retain(|key, _| !SERVER_FIELDS.contains(&key));
+        strip_backup_fields(&mut settings);
+        let template = Template {
+            name: name.to_string(),
+            description: Some(format!("Made from {}", server.server_name)),
+            server: settings,
+            properties: read_properties(&server.location.join("server.properties"))?
+                .into_iter()
+                .filter(|(key, _)| !INSTANCE_PROPERTIES.contains(&key.as_str()))
+                .collect(),
+        };
+
+        let mut exclude: Vec<PathBuf> = GENERATED_FILES
+            .iter()
+            .chain(&[console::BRIDGE_FILE])
+            .map(|file| server.location.join(file))
+            .collect();
+        exclude.push(server.jar_path());
+        for dir in [ROLLBACK_DIR, DISABLED_DIR] {
+            exclude.push(server.location.join("plugins").join(dir));
+        }
+        exclude.extend(world_dirs(server)?.iter().map(|w| server.location.join(w)));
+        let files = self.files(name)?;
+        for file in walk_files(&server.location, &exclude)? {
+            let target = files.join(&file);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(server.location.join(&file), target)?;
+        }
+        self.save(&template)?;
+        Ok(template)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), Error> {
+        self.get(name)?;
+        std::fs::remove_dir_all(self.dir(name)?)?;
+        Ok(())
+    }
+
+    /// Lays a template down in a new server folder: copies the skeleton, then sets its
+    /// `server_box.toml` fields and properties.
+    pub fn apply(&self, template: &Template, server: &Server) -> Result<Server, Error> {
+        let files = self.files(&template.name)?;
+        if files.is_dir() {
+            copy_dir(&files, &server.location)?;
+        }
+        let mut settings = toml::Table::try_from(server)?;
+        let mut defaults = template.server.clone();
+        strip_backup_fields(&mut defaults);
+        for (key, value) in defaults {
+            if !SERVER_FIELDS.contains(&key.as_str()) {
+                settings.insert(key, value);
+            }
+        }
+        let applied: Server = settings.try_into()?;
+        applied.write();
+
+        let manipulator = ServerManipulator {
+            server: applied.clone(),
+        };
+        for (key, value) in &template.properties {
+            manipulator.set_property(key, value)?;
+        }
+        Ok(applied)
+    }
+}
+
+// Removes the per-server parts of the `[backup]` table, templates made before they were
+// left out may still have them
+fn strip_backup_fields(settings: &mut toml::Table) {
+    if let Some(backup) = settings
+        .get_mut("backup")
+        .and_then(toml::Value::as_table_mut)
+    {
+        backup.retain(|key, _| !SERVER_BACKUP_FIELDS.contains(&key));
+    }
+}
+
+fn read_properties(path: &Path) -> Result<BTreeMap<String, String>, Error> {
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
@@ -0,0 +1,144 @@
+#![warn(
+    clippy::cognitive_complexity,
+    clippy::debug_assert_with_mut_call,
+    clippy::doc_markdown,
+    clippy::enum_glob_use,
+    clippy::pedantic,
+    clippy::complexity
+)]
+
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::utils::{colorize, data_dir, format_size, move_dir, walk_files, Color};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// Written as <id>.toml next to the trashed folder <id>/
+#[derive(Deserialize, Serialize, Clone)]
+pub struct TrashEntry {
+    pub id: String,
+    pub server_name: String,
+    pub location: PathBuf,
+    pub deleted: DateTime<Local>,
+    pub size: u64,
+}
+
+impl TrashEntry {
+    pub fn print(&self) {
+        println!(
+            "  🗑️ {} ({}, deleted {}, {}) from {}",
+            colorize(&self.id, Color::Gold),
+            self.server_name,
+            self.deleted.format("%Y-%m-%d %H:%M:%S"),
+            format_size(self.size),
+            self.location.display()
+        );
+    }
+}
+
+/// Where deleted servers go until the trash is emptied.
+pub struct Trash {
+    pub root: PathBuf,
+}
+
+impl Trash {
+    pub fn open() -> Result<Self, Error> {
+        Self::at(&data_dir()?.join("trash"))
+    }
+
+    pub fn at(root: &Path) -> Result<Self, Error> {
+        std::fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Moves the server folder into the trash.
+    pub fn put(&self, server: &Server) -> Result<TrashEntry, Error> {
+        let deleted = Local::now();
+        let base_id = format!(
+            "{}-{}",
+            server
+                .server_name
+                .replace(|c: char| !c.is_alphanumeric(), "_"),
+            deleted.format("%Y%m%d-%H%M%S")
+        );
+        let mut id = base_id.clone();
+        let mut attempt = 1;
+        while self.root.join(&id).exists() {
+            attempt += 1;
+            id = format!("{base_id}-{attempt}");
+        }
+        let size = walk_files(&server.location, &[])?
+            .iter()
+            .filter_map(|f| server.location.join(f).metadata().ok())
+            .map(|m| m.len())
+            .sum();
+        let entry = TrashEntry {
+            id,
+            server_name: server.server_name.clone(),
+            location: server.location.clone(),
+            deleted,
+            size,
+        };
+        move_dir(&server.location, &self.root.join(&entry.id))?;
+        std::fs::write(
+            self.root.join(format!("{}.toml", entry.id)),
+            toml::to_string(&entry)?,
+        )?;
+        Ok(entry)
+    }
+
+    /// Everything in the trash, most recently deleted first.
+    pub fn list(&self) -> Result<Vec<TrashEntry>, Error> {
+        let mut entries = Vec::new();
+        for file in std::fs::read_dir(&self.root)? {
+            let path = file?.path();
+            if path.extension().unwrap_or_default() != "toml" {
+                continue;
+            }
+            let Ok(entry) = toml::from_str::<TrashEntry>(&std::fs::read_to_string(&path)?) else {
+                continue;
+            };
+            if self.root.join(&entry.id).is_dir() {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by_key(|e| std::cmp::Reverse(e.deleted));
+        Ok(entries)
+    }
+
+    // By id, or the most recently deleted server with that name
+    pub fn find(&self, query: &str) -> Result<TrashEntry, Error> {
+        self.list()?
+            .into_iter()
+            .find(|e| e.id == query || e.server_name.eq_ignore_ascii_case(query))
+            .ok_or(Error::ResourceNotFound(format!(
+                "{query} is not in the trash"
+            )))
+    }
+
+    /// Moves a server back to where it was deleted from.
+    pub fn restore(&self, entry: &TrashEntry) -> Result<(), Error> {
+        if entry.location.exists() {
+            return Err(Error::ResourceNotFound(format!(
+                "{} already exists, move it away first",
+                entry.location.display()
+            )));
+        }
+        if let Some(parent) = entry.location.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        move_dir(&self.root.join(&entry.id), &entry.location)?;
+        std::fs::remove_file(self.root.join(format!("{}.toml", entry.id)))?;
+        Ok(())
+    }
+
+    /// Permanently deletes a server from the trash.
+    pub fn remove(&self, entry: &TrashEntry) -> Result<(), Error> {
+        std::fs::remove_dir_all(self.root.join(&entry.id))?;
+        std::fs::remove_file(self.root.join(format!("{}.toml", entry.id)))?;
+        Ok(())
+    }
+}
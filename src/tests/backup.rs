@@ -0,0 +1,290 @@
+use crate::error::Error;
+use crate::minecraft::backup::{
+    check, create, directory, extract, list, prune, restore, select_prunable, BackupFormat,
+    BackupManifest, BackupSettings,
+};
+use crate::tests::paper_server;
+use crate::tests::repository::noise;
+use crate::tests::temp_dir;
+use chrono::{Duration, Local, Timelike};
+use std::fs::File;
+
+// Counted from noon today, so the hours never cross into another day
+fn manifest(id: &str, days_ago: i64, hours_ago: i64, size: u64) -> BackupManifest {
+    let noon = Local::now()
+        .with_hour(12)
+        .and_then(|now| now.with_minute(0))
+        .unwrap();
+    BackupManifest {
+        id: id.to_string(),
+        server_name: "survival".to_string(),
+        created: noon - Duration::days(days_ago) - Duration::hours(hours_ago),
+        format: BackupFormat::TarZst,
+        worlds_only: false,
+        size,
+        stored: Some(size),
+        files: Vec::new(),
+    }
+}
+
+fn ids(backups: &[&BackupManifest]) -> Vec<String> {
+    backups.iter().map(|b| b.id.clone()).collect()
+}
+
+#[test]
+fn creates_lists_and_prunes_backups() -> Result<(), Error> {
+    let mut server = paper_server("backup-create");
+    let backups = temp_dir("backup-create-archives");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::create_dir_all(server.location.join("world/region"))?;
+    std::fs::write(server.location.join("world/level.dat"), b"level")?;
+    std::fs::write(server.location.join("world/region/r.0.0.mca"), b"region")?;
+    std::fs::write(server.location.join("server.properties"), b"motd=hi")?;
+
+    let worlds = create(&server, BackupFormat::Zip, true)?;
+    let full = create(&server, BackupFormat::TarZst, false)?;
+
+    let mut paths: Vec<&str> = worlds.files.iter().map(|f| f.path.as_str()).collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["world/level.dat", "world/region/r.0.0.mca"]);
+    let archive = server.backup.directory.clone().unwrap();
+    let zip = zip::ZipArchive::new(File::open(worlds.archive(&archive))?)?;
+    assert_eq!(zip.len(), 2);
+    assert!(full.files.iter().any(|f| f.path == "server.properties"));
+    let tar = zstd::Decoder::new(File::open(full.archive(&archive))?)?;
+    assert_eq!(tar::Archive::new(tar).entries()?.count(), full.files.len());
+
+    let backups = list(&server)?;
+    assert_eq!(backups.len(), 2);
+    assert_eq!(backups[0].id, full.id);
+
+    server.backup.keep_last = Some(1);
+    let worlds_size = std::fs::metadata(worlds.archive(&archive))?.len();
+    assert_eq!(prune(&server, true)?.1, worlds_size);
+    let (pruned, freed) = prune(&server, false)?;
+    assert_eq!(freed, worlds_size);
+    assert_eq!(pruned.len(), 1);
+    assert_eq!(pruned[0].id, worlds.id);
+    assert!(!worlds.archive(&archive).exists());
+    assert_eq!(list(&server)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn servers_sharing_a_backup_directory_only_see_their_own_backups() -> Result<(), Error> {
+    let shared = temp_dir("backup-shared-archives");
+    let mut lobby = paper_server("backup-shared-lobby");
+    let mut arena = paper_server("backup-shared-arena");
+    for server in [&mut lobby, &mut arena] {
+        server.backup.directory = Some(shared.to_path_buf());
+        server.backup.keep_last = Some(1);
+        let motd = format!("motd={}", server.server_name);
+        std::fs::write(server.location.join("server.properties"), motd)?;
+    }
+    let arena_backup = create(&arena, BackupFormat::Incremental, false)?;
+    create(&lobby, BackupFormat::Incremental, false)?;
+    create(&lobby, BackupFormat::Incremental, false)?;
+
+    assert_eq!(list(&lobby)?.len(), 2);
+    assert_eq!(prune(&lobby, false)?.0.len(), 1);
+
+    let arena_backups = list(&arena)?;
+    assert_eq!(arena_backups.len(), 1);
+    assert_eq!(arena_backups[0].id, arena_backup.id);
+    assert!(check(&arena, &arena_backup)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn default_backup_directories_never_collide() -> Result<(), Error> {
+    let spaced = paper_server("backup-my server");
+    let underscored = paper_server("backup-my_server");
+
+    assert_ne!(directory(&spaced)?, directory(&underscored)?);
+    Ok(())
+}
+
+#[test]
+fn retention_keeps_last_daily_and_weekly_backups() -> Result<(), Error> {
+    let backups = [
+        manifest("a", 0, 0, 10),
+        manifest("b", 0, 1, 10),
+        manifest("c", 1, 0, 10),
+        manifest("d", 1, 1, 10),
+        manifest("e", 2, 0, 10),
+        manifest("f", 14, 0, 10),
+    ];
+    let settings = BackupSettings {
+        keep_last: Some(1),
+        keep_daily: Some(2),
+        ..BackupSettings::default()
+    };
+    assert_eq!(
+        ids(&select_prunable(&backups, &settings)?),
+        ["b", "d", "e", "f"]
+    );
+
+    let settings = BackupSettings {
+        keep_weekly: Some(5),
+        ..BackupSettings::default()
+    };
+    let pruned = ids(&select_prunable(&backups, &settings)?);
+    assert!(!pruned.contains(&"a".to_string()));
+    assert!(!pruned.contains(&"f".to_string()));
+
+    assert!(select_prunable(&backups, &BackupSettings::default())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn retention_caps_total_size_but_keeps_newest() -> Result<(), Error> {
+    let backups = [
+        manifest("a", 0, 0, 600),
+        manifest("b", 1, 0, 300),
+        manifest("c", 2, 0, 300),
+    ];
+    let settings = BackupSettings {
+        max_total_size: Some("1K".to_string()),
+        ..BackupSettings::default()
+    };
+    assert_eq!(ids(&select_prunable(&backups, &settings)?), ["c"]);
+
+    let settings = BackupSettings {
+        max_total_size: Some("100".to_string()),
+        ..BackupSettings::default()
+    };
+    assert_eq!(ids(&select_prunable(&backups, &settings)?), ["b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn incremental_backups_only_store_changed_chunks() -> Result<(), Error> {
+    let mut server = paper_server("backup-incremental");
+    let archive = temp_dir("backup-incremental-archives");
+    server.backup.directory = Some(archive.to_path_buf());
+    let region = server.location.join("world/region/r.0.0.mca");
+    std::fs::create_dir_all(region.parent().unwrap())?;
+    std::fs::write(server.location.join("world/level.dat"), b"level")?;
+    let original = noise(4, 2 * 1024 * 1024);
+    std::fs::write(&region, &original)?;
+
+    let first = create(&server, BackupFormat::Incremental, true)?;
+    let mut edited = original.clone();
+    edited[1_000_000..1_000_100].fill(7);
+    std::fs::write(&region, &edited)?;
+    let second = create(&server, BackupFormat::Incremental, true)?;
+
+    assert!(first.stored.unwrap() > 1024 * 1024);
+    assert!(second.stored.unwrap() < first.stored.unwrap() / 4);
+    // Retention sees the whole world in both
+    assert_eq!(second.size, first.size);
+    assert!(second.size > 2 * 1024 * 1024);
+
+    let restored = temp_dir("backup-incremental-restored");
+    extract(&server, &first, &restored)?;
+    assert_eq!(
+        std::fs::read(restored.join("world/region/r.0.0.mca"))?,
+        original
+    );
+    extract(&server, &second, &restored)?;
+    assert_eq!(
+        std::fs::read(restored.join("world/region/r.0.0.mca"))?,
+        edited
+    );
+    assert!(check(&server, &second)?.is_empty());
+
+    // Pruning the first backup keeps every chunk the second one needs
+    server.backup.keep_last = Some(1);
+    assert!(prune(&server, false)?.1 > 0);
+    assert!(check(&server, &second)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn check_reports_damaged_chunks() -> Result<(), Error> {
+    let mut server = paper_server("backup-damaged");
+    let archive = temp_dir("backup-damaged-archives");
+    server.backup.directory = Some(archive.to_path_buf());
+    std::fs::write(server.location.join("data.bin"), noise(5, 50_000))?;
+    let backup = create(&server, BackupFormat::Incremental, false)?;
+
+    let file = backup.files.iter().find(|f| f.path == "data.bin").unwrap();
+    let hex = file.chunks[0].trim_start_matches("sha256:");
+    let chunk = archive.join("repository/chunks").join(&hex[..2]).join(hex);
+    std::fs::write(chunk, zstd::encode_all(&b"garbage"[..], 3)?)?;
+
+    let problems = check(&server, &backup)?;
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("data.bin"));
+    assert!(extract(&server, &backup, &temp_dir("backup-damaged-restored")).is_err());
+    Ok(())
+}
+
+#[test]
+fn restores_a_server_after_taking_a_safety_backup() -> Result<(), Error> {
+    let mut server = paper_server("backup-restore");
+    let backups = temp_dir("backup-restore-archives");
+    server.backup.directory = Some(backups.to_path_buf());
+    server.write();
+    std::fs::create_dir_all(server.location.join("world"))?;
+    std::fs::write(server.location.join("world/level.dat"), b"old level")?;
+    std::fs::write(server.location.join("server.properties"), b"motd=old")?;
+    let backup = create(&server, BackupFormat::TarZst, false)?;
+
+    std::fs::write(server.location.join("world/level.dat"), b"new level")?;
+    std::fs::write(server.location.join("server.properties"), b"motd=new")?;
+    std::fs::write(server.location.join("extra.txt"), b"added later")?;
+    let mut upgraded = server.clone();
+    upgraded.version = "1.21".to_string();
+    upgraded.write();
+
+    let (safety, restored) = restore(&server, &backup, false)?;
+
+    assert_eq!(
+        std::fs::read(server.location.join("world/level.dat"))?,
+        b"old level"
+    );
+    assert!(!server.location.join("extra.txt").exists());
+    assert_eq!(restored.version, "1.20.4");
+    assert_eq!(restored.location, server.location);
+    assert!(safety.files.iter().any(|f| f.path == "extra.txt"));
+    let prefix = format!(
+        ".{}.",
+        server.location.file_name().unwrap().to_string_lossy()
+    );
+    let siblings = std::fs::read_dir(server.location.parent().unwrap())?
+        .filter_map(Result::ok)
+        .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+        .count();
+    assert_eq!(siblings, 0);
+    Ok(())
+}
+
+#[test]
+fn restores_only_the_worlds() -> Result<(), Error> {
+    let mut server = paper_server("backup-restore-worlds");
+    let backups = temp_dir("backup-restore-worlds-archives");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::create_dir_all(server.location.join("world"))?;
+    std::fs::write(server.location.join("world/level.dat"), b"old level")?;
+    std::fs::write(server.location.join("server.properties"), b"motd=old")?;
+    let backup = create(&server, BackupFormat::Zip, false)?;
+
+    std::fs::write(server.location.join("world/level.dat"), b"new level")?;
+    std::fs::write(server.location.join("world/new.dat"), b"new file")?;
+    std::fs::write(server.location.join("server.properties"), b"motd=new")?;
+
+    let (safety, _) = restore(&server, &backup, true)?;
+
+    assert!(safety.worlds_only);
+    assert_eq!(
+        std::fs::read(server.location.join("world/level.dat"))?,
+        b"old level"
+    );
+    assert!(!server.location.join("world/new.dat").exists());
+    assert_eq!(
+        std::fs::read(server.location.join("server.properties"))?,
+        b"motd=new"
+    );
+    Ok(())
+}
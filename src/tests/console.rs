@@ -0,0 +1,152 @@
+use crate::error::Error;
+use crate::minecraft::console::{open_bridge, wait_for_log_while, with_saving_paused, Console};
+use crate::tests::{paper_server, temp_dir, TestServer};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn read_packet(stream: &mut TcpStream) -> Option<(i32, i32, String)> {
+    let mut int = [0; 4];
+    stream.read_exact(&mut int).ok()?;
+    let mut packet = vec![0; usize::try_from(i32::from_le_bytes(int)).ok()?];
+    stream.read_exact(&mut packet).ok()?;
+    let id = i32::from_le_bytes(packet[0..4].try_into().ok()?);
+    let kind = i32::from_le_bytes(packet[4..8].try_into().ok()?);
+    let body = String::from_utf8_lossy(&packet[8..packet.len() - 2]).to_string();
+    Some((id, kind, body))
+}
+
+fn write_packet(stream: &mut TcpStream, id: i32, body: &str) {
+    let mut packet = Vec::new();
+    packet.extend_from_slice(&i32::try_from(body.len() + 10).unwrap().to_le_bytes());
+    packet.extend_from_slice(&id.to_le_bytes());
+    packet.extend_from_slice(&0i32.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    stream.write_all(&packet).unwrap();
+}
+
+// An RCON server that answers "save-all flush" like a Minecraft server would
+fn rcon_server() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let commands = Arc::new(Mutex::new(Vec::new()));
+    let received = commands.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            while let Some((id, kind, body)) = read_packet(&mut stream) {
+                if kind == 3 {
+                    write_packet(&mut stream, if body == "secret" { id } else { -1 }, "");
+                    continue;
+                }
+                let response = if body == "save-all flush" {
+                    "Saving the game (this may take a moment!)Saved the game"
+                } else {
+                    ""
+                };
+                received.lock().unwrap().push(body);
+                write_packet(&mut stream, id, response);
+            }
+        }
+    });
+    (port, commands)
+}
+
+fn rcon_enabled_server(name: &str) -> (TestServer, Arc<Mutex<Vec<String>>>) {
+    let server = paper_server(name);
+    let (port, commands) = rcon_server();
+    std::fs::write(
+        server.location.join("server.properties"),
+        format!("enable-rcon=true\nrcon.port={port}\nrcon.password=secret\n"),
+    )
+    .unwrap();
+    (server, commands)
+}
+
+#[test]
+fn pauses_saving_until_the_world_is_flushed() -> Result<(), Error> {
+    let (server, commands) = rcon_enabled_server("console-pause");
+
+    let seen = with_saving_paused(&server, Duration::from_secs(5), || {
+        Ok(commands.lock().unwrap().clone())
+    })?;
+
+    assert_eq!(seen, ["save-off", "save-all flush"]);
+    assert_eq!(
+        *commands.lock().unwrap(),
+        ["save-off", "save-all flush", "save-on"]
+    );
+    Ok(())
+}
+
+#[test]
+fn resumes_saving_when_the_backup_fails() {
+    let (server, commands) = rcon_enabled_server("console-failure");
+
+    let result: Result<(), Error> = with_saving_paused(&server, Duration::from_secs(5), || {
+        Err(Error::ResourceNotFound("disk full".to_string()))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(commands.lock().unwrap().last().unwrap(), "save-on");
+}
+
+#[test]
+fn forwards_bridge_commands_to_the_server() -> Result<(), Error> {
+    let server = paper_server("console-bridge");
+    let output = server.location.join("stdin.log");
+    // Stands in for the server, writing down what reaches its stdin
+    let mut child = Command::new("sh")
+        .args(["-c", "cat > stdin.log"])
+        .current_dir(&server.location)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    let bridge = open_bridge(&server.location, child.stdin.take().unwrap())?;
+
+    let mut console = Console::connect(&server)?;
+    assert!(matches!(console, Console::Bridge { .. }));
+    console.send("say hello")?;
+
+    let started = Instant::now();
+    let mut received = String::new();
+    while !received.contains("say hello") && started.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(50));
+        received = std::fs::read_to_string(&output).unwrap_or_default();
+    }
+    child.kill()?;
+    child.wait()?;
+    assert_eq!(received, "say hello\n");
+    assert_eq!(std::fs::read_to_string(&bridge)?.split(' ').count(), 2);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(
+            std::fs::metadata(&bridge)?.permissions().mode() & 0o777,
+            0o600
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn stops_waiting_for_the_log_when_the_check_fails() -> Result<(), Error> {
+    let dir = temp_dir("console-wait");
+    let log = dir.join("latest.log");
+    let mut child = Command::new("sh").args(["-c", "exit 3"]).spawn()?;
+
+    let started = Instant::now();
+    let result = wait_for_log_while(&log, 0, "Done (", Duration::from_secs(30), || {
+        match child.try_wait()? {
+            Some(_) => Err(Error::Console("exited".to_string())),
+            None => Ok(()),
+        }
+    });
+
+    assert!(matches!(result, Err(Error::Console(message)) if message == "exited"));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    Ok(())
+}
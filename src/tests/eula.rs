@@ -0,0 +1,48 @@
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::tests::paper_server;
+
+#[test]
+fn writes_eula_before_the_first_start() -> Result<(), Error> {
+    let mut server = paper_server("eula-first-start");
+    assert!(!server.eula_accepted());
+
+    server.remember_eula()?;
+
+    let eula = std::fs::read_to_string(server.location.join("eula.txt"))?;
+    assert!(eula.starts_with("#By changing the setting below to TRUE"));
+    assert!(eula.ends_with("eula=true\n"));
+    assert!(server.eula_accepted());
+    let saved = Server::from_path(&server.location.to_string_lossy())?;
+    assert!(saved.accept_eula);
+    Ok(())
+}
+
+#[test]
+fn accepts_an_existing_eula() -> Result<(), Error> {
+    let server = paper_server("eula-existing");
+    std::fs::write(
+        server.location.join("eula.txt"),
+        "#Generated by the server\neula=false\n",
+    )?;
+
+    server.accept_eula()?;
+
+    assert_eq!(
+        std::fs::read_to_string(server.location.join("eula.txt"))?,
+        "#Generated by the server\neula=true\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn proxies_get_no_eula() -> Result<(), Error> {
+    let mut server = paper_server("eula-proxy");
+    server.jar_name = "Velocity".to_string();
+
+    server.remember_eula()?;
+
+    assert!(server.accept_eula);
+    assert!(!server.location.join("eula.txt").exists());
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::cli::constructor::GenerateArgs;
+use crate::cli::generator::{create_location, prompt_wizard, resolve_build, resolve_version};
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::jars::Jar;
+use crate::tests::http::serve;
+use crate::tests::temp_dir;
+
+fn paper_api() -> (crate::tests::http::MockServer, Jar) {
+    let mock = serve(|_| {
+        vec![
+            (
+                "/paper".to_string(),
+                br#"{"project_id":"paper","project_name":"Paper","versions":["1.20.4","1.20.6"]}"#
+                    .to_vec(),
+            ),
+            (
+                "/paper/1.20.6".to_string(),
+                br#"{"project_id":"paper","project_name":"Paper","version":"1.20.6","builds":[140,147]}"#
+                    .to_vec(),
+            ),
+        ]
+    });
+    let jar = Jar {
+        name: "Paper".to_string(),
+        download_url: format!("{}/paper/{{version}}/{{build}}.jar", mock.url),
+        builds_url: format!("{}/paper/{{version}}", mock.url),
+        versions_url: format!("{}/paper", mock.url),
+        changelog_url: None,
+    };
+    (mock, jar)
+}
+
+#[test]
+fn resolves_latest_version_and_build() -> Result<(), Error> {
+    let (_mock, jar) = paper_api();
+
+    assert_eq!(resolve_version(&jar, "latest")?, "1.20.6");
+    assert_eq!(resolve_version(&jar, "1.20.4")?, "1.20.4");
+    assert!(resolve_version(&jar, "1.8.8").is_err());
+    assert_eq!(resolve_build(&jar, "1.20.6", Some("latest"))?, "147");
+    assert_eq!(resolve_build(&jar, "1.20.6", None)?, "147");
+    assert_eq!(resolve_build(&jar, "1.20.6", Some("140"))?, "140");
+    Ok(())
+}
+
+#[test]
+fn names_the_missing_flag_instead_of_prompting() {
+    let mut config = Config::default();
+    let args = GenerateArgs {
+        name: Some("ci-server".to_string()),
+        non_interactive: true,
+        ..GenerateArgs::default()
+    };
+
+    let error = prompt_wizard(&mut config, args).err().unwrap();
+
+    assert!(error.to_string().contains("--jar"));
+    assert!(config.servers.is_empty());
+}
+
+#[test]
+fn rejects_unknown_jars() {
+    let mut config = Config::default();
+    let args = GenerateArgs {
+        name: Some("ci-unknown".to_string()),
+        jar: Some("Bedrock".to_string()),
+        non_interactive: true,
+        ..GenerateArgs::default()
+    };
+
+    let error = prompt_wizard(&mut config, args).err().unwrap();
+
+    assert!(error.to_string().contains("available: Paper"));
+}
+
+#[test]
+fn creates_missing_locations_with_create_dir() -> Result<(), Error> {
+    let root = temp_dir("generator-location");
+    let location = root.join("servers/lobby");
+
+    let created = create_location(&location.to_string_lossy(), true)?;
+
+    assert!(location.is_dir());
+    assert!(created.is_absolute());
+    Ok(())
+}
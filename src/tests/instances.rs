@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crate::minecraft::instances::{clone_server, ports, relocate, rename, CloneOptions};
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::tests::paper_server;
+use crate::tests::temp_dir;
+use crate::utils::{canonize, move_dir};
+
+#[test]
+fn clones_a_server_with_new_ports() -> Result<(), Error> {
+    let mut source = paper_server("clone-source");
+    let backups = temp_dir("clone-source-backups");
+    source.backup.directory = Some(backups.to_path_buf());
+    source.write();
+    std::fs::create_dir_all(source.location.join("world"))?;
+    std::fs::create_dir_all(source.location.join("plugins"))?;
+    std::fs::write(source.location.join("world/level.dat"), b"level")?;
+    std::fs::write(source.location.join("plugins/Core.jar"), b"core")?;
+    std::fs::write(source.location.join("Paper-1.20.4.jar"), b"paper")?;
+    std::fs::write(
+        source.location.join("server.properties"),
+        "# Minecraft server properties\nmotd=lobby\nserver-port=25565\nenable-rcon=true\nrcon.port=25575\n",
+    )?;
+    let location = temp_dir("clone-target");
+
+    let options = CloneOptions {
+        without_worlds: true,
+        without_plugins: false,
+    };
+    let (clone, report) =
+        clone_server(&source, "lobby-test", &location, &options, &[25565, 25575])?;
+
+    assert_eq!(clone.server_name, "lobby-test");
+    assert_eq!(clone.backup.directory, None);
+    assert!(!location.join("world").exists());
+    assert_eq!(std::fs::read(location.join("plugins/Core.jar"))?, b"core");
+    assert_eq!(
+        report.copied + report.reflinked + report.hardlinked,
+        4 // server_box.toml, server.properties and two jars
+    );
+    let ports = ports(&clone);
+    assert_eq!(ports.len(), 2);
+    assert!(!ports.contains(&25565) && !ports.contains(&25575));
+    let manipulator = ServerManipulator { server: clone };
+    assert_eq!(manipulator.get_property("motd").as_deref(), Some("lobby"));
+    let properties = std::fs::read_to_string(location.join("server.properties"))?;
+    assert!(properties.starts_with("# Minecraft server properties\nmotd=lobby\n"));
+
+    let info = std::fs::read_to_string(location.join("server_box.toml"))?;
+    assert!(info.contains("server_name = \"lobby-test\""));
+    Ok(())
+}
+
+#[test]
+fn refuses_to_clone_into_a_non_empty_folder() {
+    let source = paper_server("clone-occupied-source");
+    let location = temp_dir("clone-occupied");
+    std::fs::write(location.join("file.txt"), b"taken").unwrap();
+
+    let result = clone_server(&source, "copy", &location, &CloneOptions::default(), &[]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn renames_a_server() -> Result<(), Error> {
+    let mut server = paper_server("rename");
+    let backups = temp_dir("rename-backups");
+    server.backup.directory = Some(backups.to_path_buf());
+
+    let renamed = rename(&server, "renamed")?;
+
+    assert_eq!(renamed.server_name, "renamed");
+    let info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("server_name = \"renamed\""));
+    Ok(())
+}
+
+#[test]
+fn moves_a_server_to_a_new_folder() -> Result<(), Error> {
+    let server = paper_server("relocate");
+    std::fs::create_dir_all(server.location.join("world"))?;
+    std::fs::write(server.location.join("world/level.dat"), b"level")?;
+    let root = temp_dir("relocate-target");
+    let target = root.join("nested/server");
+
+    let moved = relocate(&server, &target)?;
+
+    assert!(!server.location.exists());
+    assert_eq!(std::fs::read(target.join("world/level.dat"))?, b"level");
+    assert_eq!(moved.location, canonize(&target)?);
+    let info: Server = toml::from_str(&std::fs::read_to_string(target.join("server_box.toml"))?)?;
+    assert_eq!(info.location, moved.location);
+    Ok(())
+}
+
+#[test]
+fn refuses_to_move_a_server_into_its_own_folder() -> Result<(), Error> {
+    let server = paper_server("relocate-nested");
+    std::fs::write(server.location.join("level.dat"), b"level")?;
+
+    assert!(matches!(
+        relocate(&server, &server.location.join("sub")),
+        Err(Error::InvalidInput(_))
+    ));
+
+    assert_eq!(std::fs::read(server.location.join("level.dat"))?, b"level");
+    assert!(!server.location.join("sub").exists());
+    Ok(())
+}
+
+#[test]
+fn never_moves_onto_an_existing_folder() -> Result<(), Error> {
+    let from = temp_dir("move-dir-from");
+    let to = temp_dir("move-dir-to");
+    std::fs::write(from.join("a.txt"), b"a")?;
+    std::fs::write(to.join("b.txt"), b"b")?;
+
+    assert!(matches!(move_dir(&from, &to), Err(Error::InvalidInput(_))));
+
+    assert_eq!(std::fs::read(from.join("a.txt"))?, b"a");
+    assert_eq!(std::fs::read(to.join("b.txt"))?, b"b");
+    Ok(())
+}
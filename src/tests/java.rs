@@ -0,0 +1,100 @@
+use crate::error::Error;
+use crate::java::runtime::{
+    find, from_homes, major_version, required_java, runtime_homes, select, JavaRequirement,
+};
+use crate::tests::paper_server;
+use crate::tests::temp_dir;
+use std::path::{Path, PathBuf};
+
+// A runtime folder with a release file and an (empty) java executable
+pub fn fake_runtime(root: &Path, name: &str, version: &str) -> PathBuf {
+    let home = root.join(name);
+    std::fs::create_dir_all(home.join("bin")).unwrap();
+    std::fs::write(home.join("bin/java"), b"").unwrap();
+    std::fs::write(home.join("bin/java.exe"), b"").unwrap();
+    std::fs::write(
+        home.join("release"),
+        format!("IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"{version}\"\n"),
+    )
+    .unwrap();
+    home
+}
+
+#[test]
+fn reads_major_versions() {
+    assert_eq!(major_version("1.8.0_392"), Some(8));
+    assert_eq!(major_version("17.0.9"), Some(17));
+    assert_eq!(major_version("21"), Some(21));
+    assert_eq!(major_version("ea"), None);
+}
+
+#[test]
+fn maps_minecraft_versions_to_java() {
+    let java = |version| required_java("Paper", version).unwrap();
+
+    assert_eq!(java("1.12.2"), JavaRequirement::exactly(8));
+    assert!(java("1.16.5").allows(11));
+    assert!(!java("1.16.5").allows(17));
+    assert_eq!(java("1.17.1").min, 16);
+    assert_eq!(java("1.20.4").min, 17);
+    assert_eq!(java("1.20.5").min, 21);
+    assert_eq!(java("1.21").max, None);
+    assert_eq!(required_java("Velocity", "3.3.0-SNAPSHOT").unwrap().min, 17);
+    assert_eq!(required_java("Paper", "Unknown"), None);
+}
+
+#[test]
+fn discovers_runtimes_from_release_files() {
+    let root = temp_dir("java-discover");
+    fake_runtime(&root, "temurin-8", "1.8.0_392");
+    fake_runtime(&root, "temurin-17", "17.0.9");
+    fake_runtime(&root, "temurin-21", "21.0.1");
+    std::fs::create_dir_all(root.join("not-a-jdk")).unwrap();
+
+    let runtimes = from_homes(&runtime_homes(&root));
+
+    let majors: Vec<u32> = runtimes.iter().map(|r| r.major).collect();
+    assert_eq!(majors, vec![21, 17, 8]);
+    assert_eq!(runtimes[0].vendor.as_deref(), Some("Eclipse Adoptium"));
+}
+
+#[test]
+fn picks_the_oldest_allowed_major_in_its_newest_release() {
+    let root = temp_dir("java-select");
+    fake_runtime(&root, "jdk-17.0.2", "17.0.2");
+    fake_runtime(&root, "jdk-17.0.9", "17.0.9");
+    fake_runtime(&root, "jdk-21", "21.0.1");
+    let runtimes = from_homes(&runtime_homes(&root));
+
+    let modern = select(&runtimes, required_java("Paper", "1.20.4").unwrap()).unwrap();
+    let latest = select(&runtimes, required_java("Paper", "1.21").unwrap()).unwrap();
+
+    assert_eq!(modern.version, "17.0.9");
+    assert_eq!(latest.major, 21);
+}
+
+#[test]
+fn names_the_missing_java_version() {
+    let root = temp_dir("java-missing");
+    fake_runtime(&root, "jdk-21", "21.0.1");
+    let runtimes = from_homes(&runtime_homes(&root));
+
+    let result = find(&runtimes, required_java("Paper", "1.12.2").unwrap());
+
+    assert!(matches!(result, Err(Error::JavaNotFound(version)) if version == "8"));
+}
+
+#[test]
+fn uses_the_configured_java_path() -> Result<(), Error> {
+    let root = temp_dir("java-configured");
+    let home = fake_runtime(&root, "jdk", "17.0.9");
+    let mut server = paper_server("java-configured-server");
+    server.java_path = Some(home.clone());
+
+    assert_eq!(
+        server.java_executable()?,
+        home.join("bin")
+            .join(if cfg!(windows) { "java.exe" } else { "java" })
+    );
+    Ok(())
+}
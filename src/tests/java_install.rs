@@ -0,0 +1,87 @@
+use crate::error::Error;
+use crate::java::install::{installed, remove, Adoptium, ImageType};
+use crate::tests::http::serve;
+use crate::tests::temp_dir;
+use crate::utils::hash_bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+// A Temurin-like tar.gz holding jdk-21.0.1+12/{release,bin/java}
+fn jdk_archive() -> Vec<u8> {
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+    let files: [(&str, &[u8], u32); 2] = [
+        (
+            "jdk-21.0.1+12/release",
+            b"IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.1\"\n",
+            0o644,
+        ),
+        ("jdk-21.0.1+12/bin/java", b"#!/bin/sh\n", 0o755),
+    ];
+    for (path, body, mode) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        tar.append_data(&mut header, path, body).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap()
+}
+
+fn adoptium(archive: Vec<u8>, checksum: &str) -> (crate::tests::http::MockServer, Adoptium) {
+    let checksum = checksum.to_string();
+    let mock = serve(move |url| {
+        vec![
+            (
+                "/assets/latest/21/hotspot".to_string(),
+                format!(
+                    r#"[{{"binary":{{"image_type":"jdk","package":{{"name":"OpenJDK21U-jdk_x64_linux_hotspot_21.0.1_12.tar.gz","link":"{url}/jdk.tar.gz","checksum":"{checksum}","size":1}}}},"release_name":"jdk-21.0.1+12"}}]"#
+                )
+                .into_bytes(),
+            ),
+            ("/jdk.tar.gz".to_string(), archive),
+        ]
+    });
+    let api = Adoptium::new(&mock.url).unwrap();
+    (mock, api)
+}
+
+#[test]
+fn installs_lists_and_removes_a_jdk() -> Result<(), Error> {
+    let archive = jdk_archive();
+    let checksum = hash_bytes("sha256", &archive)?;
+    let (mock, api) = adoptium(archive, checksum.trim_start_matches("sha256:"));
+    let root = temp_dir("java-install");
+
+    let package = api.find(21, ImageType::Jdk)?;
+    let runtime = api.install(&package, &root)?;
+
+    assert_eq!(runtime.major, 21);
+    assert_eq!(runtime.home, root.join("jdk-21.0.1+12"));
+    assert!(mock.requests.lock().unwrap()[0].contains("image_type=jdk"));
+    assert_eq!(installed(&root).len(), 1);
+    assert!(api.install(&package, &root).is_err());
+
+    // The JRE of the same release goes next to the JDK
+    let jre = api.install(&api.find(21, ImageType::Jre)?, &root)?;
+    assert_eq!(jre.home, root.join("jdk-21.0.1+12-jre"));
+    assert_eq!(installed(&root).len(), 2);
+    remove(&root, &jre)?;
+
+    remove(&root, &runtime)?;
+    assert!(installed(&root).is_empty());
+    assert!(!root.join("jdk-21.0.1+12").exists());
+    Ok(())
+}
+
+#[test]
+fn rejects_an_archive_with_the_wrong_checksum() -> Result<(), Error> {
+    let (_mock, api) = adoptium(jdk_archive(), &"0".repeat(64));
+    let root = temp_dir("java-install-checksum");
+
+    let package = api.find(21, ImageType::Jdk)?;
+    let result = api.install(&package, &root);
+
+    assert!(matches!(result, Err(Error::HashMismatch { .. })));
+    assert_eq!(std::fs::read_dir(&root)?.count(), 0);
+    Ok(())
+}
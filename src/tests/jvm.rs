@@ -0,0 +1,115 @@
+use crate::error::Error;
+use crate::minecraft::jvm::{shell_join, JvmProfile};
+use crate::minecraft::server::Server;
+use crate::tests::java::fake_runtime;
+use crate::tests::{paper_server, TestServer};
+
+fn server_with_java(name: &str) -> TestServer {
+    let mut server = paper_server(name);
+    server.java_path = Some(fake_runtime(&server.location.join("java"), "jdk", "17.0.9"));
+    server
+}
+
+#[test]
+fn picks_a_profile_for_auto() {
+    assert_eq!(
+        JvmProfile::Auto.resolve("Paper", Some("4G")),
+        JvmProfile::Aikar
+    );
+    assert_eq!(
+        JvmProfile::Auto.resolve("Paper", Some("16G")),
+        JvmProfile::AikarLargeHeap
+    );
+    assert_eq!(
+        JvmProfile::Auto.resolve("Velocity", Some("16G")),
+        JvmProfile::Proxy
+    );
+    assert_eq!(
+        JvmProfile::ZgcGenerational.resolve("Paper", None),
+        JvmProfile::ZgcGenerational
+    );
+}
+
+#[test]
+fn large_heap_flags_replace_aikar_values() {
+    let flags = JvmProfile::AikarLargeHeap.flags();
+
+    assert_eq!(flags.len(), JvmProfile::Aikar.flags().len());
+    assert!(flags.contains(&"-XX:G1HeapRegionSize=16M"));
+    assert!(!flags.contains(&"-XX:G1HeapRegionSize=8M"));
+    assert!(flags.contains(&"-XX:SurvivorRatio=32"));
+    assert!(JvmProfile::None.flags().is_empty());
+}
+
+#[test]
+fn builds_the_command_line() -> Result<(), Error> {
+    let mut server = server_with_java("jvm-command");
+    server.gui = false;
+    server.xmx = Some("4G".to_string());
+    server.jvm.profile = JvmProfile::ZgcGenerational;
+    server.jvm.jvm_args = vec!["-Dfile.encoding=UTF-8".to_string()];
+    server.jvm.args = vec!["--world-dir".to_string(), "worlds".to_string()];
+
+    let command = server.command_line()?;
+
+    let jar = server.jar_path().to_string_lossy().to_string();
+    assert!(command[0].ends_with("java") || command[0].ends_with("java.exe"));
+    assert_eq!(
+        command[1..],
+        [
+            "-Dname=jvm-command",
+            "-Xms1G",
+            "-Xmx4G",
+            "-XX:+UseZGC",
+            "-XX:+ZGenerational",
+            "-XX:+AlwaysPreTouch",
+            "-XX:+DisableExplicitGC",
+            "-XX:+PerfDisableSharedMem",
+            "-Dfile.encoding=UTF-8",
+            "-jar",
+            &jar,
+            "--nogui",
+            "--world-dir",
+            "worlds",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn proxies_get_no_nogui() -> Result<(), Error> {
+    let mut server = server_with_java("jvm-proxy");
+    server.jar_name = "Velocity".to_string();
+    server.gui = false;
+
+    let command = server.command_line()?;
+
+    assert!(command.contains(&"-XX:MaxInlineLevel=15".to_string()));
+    assert!(!command.contains(&"--nogui".to_string()));
+    Ok(())
+}
+
+#[test]
+fn reads_the_jvm_section() -> Result<(), Error> {
+    let server = paper_server("jvm-toml");
+    let mut info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("[jvm]"));
+    info = info.replace("profile = \"auto\"", "profile = \"aikar-large-heap\"");
+    std::fs::write(server.location.join("server_box.toml"), info)?;
+
+    let server = Server::from_path(&server.location.to_string_lossy())?;
+
+    assert_eq!(server.jvm.profile, JvmProfile::AikarLargeHeap);
+    Ok(())
+}
+
+#[test]
+fn quotes_arguments_for_display() {
+    let args = vec![
+        "java".to_string(),
+        "-Dmotd=Hello world".to_string(),
+        "-Xmx4G".to_string(),
+    ];
+
+    assert_eq!(shell_join(&args), "java '-Dmotd=Hello world' -Xmx4G");
+}
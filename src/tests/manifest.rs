@@ -0,0 +1,166 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::minecraft::jars::Jar;
+use crate::minecraft::manifest::{apply, plan, Change, Manifest};
+use crate::minecraft::plugin_sources::PluginApis;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::tests::http::{serve, MockServer};
+use crate::tests::plugin_sources::modrinth;
+use crate::tests::{temp_dir, TempDir};
+use crate::utils::hash_bytes;
+
+const CORE_JAR: &[u8] = b"core jar";
+const DATAPACK: &[u8] = b"datapack zip";
+
+// Paper 1.20.4 with builds 1 and 2, and a datapack
+fn paper_api() -> (MockServer, Jar) {
+    let mock = serve(|_| {
+        vec![
+            (
+                "/paper/1.20.4".to_string(),
+                br#"{"project_id":"paper","project_name":"Paper","version":"1.20.4","builds":[1,2]}"#
+                    .to_vec(),
+            ),
+            ("/paper/1.20.4/2.jar".to_string(), b"paper 1.20.4".to_vec()),
+            ("/datapacks/terrain.zip".to_string(), DATAPACK.to_vec()),
+        ]
+    });
+    let jar = Jar {
+        name: "Paper".to_string(),
+        download_url: format!("{}/paper/{{version}}/{{build}}.jar", mock.url),
+        builds_url: format!("{}/paper/{{version}}", mock.url),
+        versions_url: format!("{}/paper", mock.url),
+        changelog_url: None,
+    };
+    (mock, jar)
+}
+
+// The manifest, with the folder holding it and the server it describes
+fn write_manifest(name: &str, url: &str) -> Result<(TempDir, Manifest), Error> {
+    let dir = temp_dir(name);
+    let sha256 = hash_bytes("sha256", DATAPACK)?;
+    let path = dir.join("server.toml");
+    std::fs::write(
+        &path,
+        format!(
+            r#"name = "{name}"
+location = "server"
+jar = "paper"
+version = "1.20.4"
+build = "latest"
+accept_eula = true
+plugins = ["core"]
+
+[memory]
+max = "2g"
+
+[properties]
+motd = "Managed"
+max-players = 40
+
+[[datapacks]]
+name = "terrain"
+url = "{url}/datapacks/terrain.zip"
+sha256 = "{}"
+"#,
+            sha256.trim_start_matches("sha256:")
+        ),
+    )?;
+    Ok((dir, Manifest::load(&path)?))
+}
+
+#[test]
+fn plans_a_new_server() -> Result<(), Error> {
+    let (mock, jar) = paper_api();
+    let (_dir, manifest) = write_manifest("manifest-plan", &mock.url)?;
+
+    let changes = plan(&manifest, &jar, false)?;
+
+    assert_eq!(
+        changes.first(),
+        Some(&Change::Create {
+            jar: "Paper 1.20.4".to_string()
+        })
+    );
+    assert!(changes.contains(&Change::Setting {
+        key: "xmx".to_string(),
+        from: "1G".to_string(),
+        to: "2G".to_string()
+    }));
+    assert!(changes.contains(&Change::Property {
+        key: "max-players".to_string(),
+        from: None,
+        to: "40".to_string()
+    }));
+    assert!(changes.contains(&Change::Plugin {
+        spec: "core".to_string(),
+        from: None
+    }));
+    assert_eq!(changes.last(), Some(&Change::Register));
+    Ok(())
+}
+
+#[test]
+fn applying_twice_is_a_no_op() -> Result<(), Error> {
+    let (mock, jar) = paper_api();
+    let api = modrinth(CORE_JAR, hash_bytes("sha512", CORE_JAR)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let (_dir, manifest) = write_manifest("manifest-apply", &mock.url)?;
+    let mut config = Config::default();
+
+    let changes = plan(&manifest, &jar, true)?;
+    let server = apply(&manifest, &changes, &jar, &apis, &mut config)?;
+
+    assert_eq!(server.build, "2");
+    assert_eq!(server.xmx.as_deref(), Some("2G"));
+    assert!(server.accept_eula);
+    assert!(manifest.location.join("plugins/core.jar").exists());
+    assert!(manifest.location.join("plugins/lib.jar").exists());
+    assert_eq!(
+        std::fs::read(manifest.location.join("world/datapacks/terrain.zip"))?,
+        DATAPACK
+    );
+    assert!(plan(&manifest, &jar, true)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn plans_property_changes_of_an_existing_server() -> Result<(), Error> {
+    let (mock, jar) = paper_api();
+    let api = modrinth(CORE_JAR, hash_bytes("sha512", CORE_JAR)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let (_dir, manifest) = write_manifest("manifest-property", &mock.url)?;
+    let changes = plan(&manifest, &jar, true)?;
+    let server = apply(&manifest, &changes, &jar, &apis, &mut Config::default())?;
+    ServerManipulator { server }.set_property("motd", "Edited by hand")?;
+
+    let changes = plan(&manifest, &jar, true)?;
+
+    assert_eq!(
+        changes,
+        vec![Change::Property {
+            key: "motd".to_string(),
+            from: Some("Edited by hand".to_string()),
+            to: "Managed".to_string()
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn refuses_a_folder_holding_another_server() -> Result<(), Error> {
+    let (mock, jar) = paper_api();
+    let (_dir, manifest) = write_manifest("manifest-other", &mock.url)?;
+    std::fs::create_dir_all(&manifest.location)?;
+    Server::new(
+        "someone-else",
+        "Paper".to_string(),
+        "1.20.4".to_string(),
+        "2".to_string(),
+        &manifest.location,
+    );
+
+    assert!(plan(&manifest, &jar, true).is_err());
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use crate::error::Error;
+use crate::minecraft::memory::{
+    auto_start_total, parse_heap, parse_meminfo, suggest, validate, HostMemory,
+};
+use crate::tests::paper_server;
+
+const GIB: u64 = 1 << 30;
+
+#[test]
+fn parses_heap_sizes() {
+    assert_eq!(parse_heap("2g").unwrap(), ("2G".to_string(), 2 * GIB));
+    assert_eq!(parse_heap("512M").unwrap().1, 512 << 20);
+    assert_eq!(parse_heap("1.5G").unwrap().0, "1536M");
+    assert_eq!(parse_heap("6GB").unwrap().0, "6G");
+    assert_eq!(parse_heap("2048M").unwrap().0, "2G");
+    assert_eq!(
+        parse_heap("2147483648").unwrap(),
+        ("2G".to_string(), 2 * GIB)
+    );
+    assert_eq!(parse_heap("3000001").unwrap().0, "3000001");
+    assert!(matches!(parse_heap("1K"), Err(Error::InvalidInput(_))));
+    assert!(matches!(parse_heap("lots"), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn refuses_a_minimum_above_the_maximum() {
+    assert_eq!(
+        validate("2G", "6G").unwrap(),
+        ("2G".to_string(), "6G".to_string())
+    );
+    assert!(matches!(validate("8G", "6G"), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn sums_the_heaps_of_auto_started_servers() {
+    let mut lobby = paper_server("memory-lobby");
+    lobby.auto_start = true;
+    lobby.xmx = Some("4G".to_string());
+    let mut survival = paper_server("memory-survival");
+    survival.auto_start = true;
+    let mut creative = paper_server("memory-creative");
+    creative.xmx = Some("8G".to_string());
+
+    // survival uses the 1G default, creative doesn't start automatically
+    assert_eq!(
+        auto_start_total(&[lobby.server, survival.server, creative.server]),
+        5 * GIB
+    );
+}
+
+#[test]
+fn reads_meminfo() {
+    let meminfo =
+        "MemTotal:       16318480 kB\nMemFree:         1204260 kB\nMemAvailable:    9123456 kB\n";
+
+    assert_eq!(
+        parse_meminfo(meminfo),
+        Some(HostMemory {
+            total: 16_318_480 * 1024,
+            available: 9_123_456 * 1024,
+        })
+    );
+    assert_eq!(parse_meminfo("MemFree: 1 kB"), None);
+}
+
+#[test]
+fn suggests_three_quarters_of_available_memory() {
+    assert_eq!(suggest(8 * GIB), "6G");
+    assert_eq!(suggest(GIB / 2), "1G");
+    assert_eq!(suggest(64 * GIB), "16G");
+}
@@ -0,0 +1,159 @@
+use crate::error::Error;
+use crate::minecraft::jars::Jar;
+use crate::minecraft::migration::{
+    migrate, orphans_plugins, to_bukkit_layout, to_vanilla_layout, Software,
+};
+use crate::tests::http::serve;
+use crate::tests::paper_server;
+use crate::tests::temp_dir;
+
+fn jar(name: &str, url: &str) -> Jar {
+    let lower = name.to_lowercase();
+    Jar {
+        name: name.to_string(),
+        download_url: format!("{url}/{lower}/{{version}}/{{build}}.jar"),
+        builds_url: format!("{url}/{lower}/{{version}}"),
+        versions_url: format!("{url}/{lower}"),
+        changelog_url: None,
+    }
+}
+
+#[test]
+fn recognises_server_software() {
+    assert_eq!(Software::of("Paper"), Software::Paper);
+    assert_eq!(Software::of("spigot"), Software::Spigot);
+    assert_eq!(Software::of("Velocity"), Software::Proxy);
+    assert_eq!(Software::of("server"), Software::Vanilla);
+    assert!(Software::Purpur.is_bukkit());
+    assert!(!Software::Vanilla.is_bukkit() && !Software::Proxy.is_bukkit());
+    assert!(Software::Purpur.config_files().contains(&"spigot.yml"));
+    assert!(!Software::Paper.config_files().contains(&"purpur.yml"));
+}
+
+#[test]
+fn converts_world_layouts_both_ways() -> Result<(), Error> {
+    let location = temp_dir("migration-layouts");
+    std::fs::create_dir_all(location.join("world/DIM-1/region"))?;
+    std::fs::create_dir_all(location.join("world/DIM1/region"))?;
+    std::fs::write(location.join("world/level.dat"), b"level")?;
+    std::fs::write(location.join("world/DIM-1/region/r.0.0.mca"), b"nether")?;
+
+    let moved = to_bukkit_layout(&location, "world")?;
+
+    assert_eq!(moved.len(), 2);
+    assert!(!location.join("world/DIM-1").exists());
+    assert_eq!(
+        std::fs::read(location.join("world_nether/DIM-1/region/r.0.0.mca"))?,
+        b"nether"
+    );
+    assert_eq!(
+        std::fs::read(location.join("world_nether/level.dat"))?,
+        b"level"
+    );
+    assert!(location.join("world_the_end/DIM1/region").is_dir());
+
+    std::fs::write(location.join("world_nether/uid.dat"), b"uid")?;
+    let moved = to_vanilla_layout(&location, "world")?;
+
+    assert_eq!(moved.len(), 2);
+    assert!(!location.join("world_nether").exists());
+    assert!(!location.join("world_the_end").exists());
+    assert_eq!(
+        std::fs::read(location.join("world/DIM-1/region/r.0.0.mca"))?,
+        b"nether"
+    );
+    Ok(())
+}
+
+#[test]
+fn refuses_to_overwrite_a_dimension() -> Result<(), Error> {
+    let location = temp_dir("migration-conflict");
+    std::fs::create_dir_all(location.join("world/DIM-1"))?;
+    std::fs::create_dir_all(location.join("world/DIM1"))?;
+    std::fs::create_dir_all(location.join("world_the_end/DIM1"))?;
+
+    assert!(to_bukkit_layout(&location, "world").is_err());
+    // Nothing was moved
+    assert!(location.join("world/DIM-1").is_dir());
+    assert!(!location.join("world_nether").exists());
+    Ok(())
+}
+
+#[test]
+fn migrates_paper_to_purpur() -> Result<(), Error> {
+    let mock = serve(|_| {
+        vec![
+            (
+                "/purpur/1.20.4".to_string(),
+                br#"{"project":"purpur","version":"1.20.4","builds":{"latest":"2","all":["1","2"]}}"#
+                    .to_vec(),
+            ),
+            ("/purpur/1.20.4/2.jar".to_string(), b"purpur".to_vec()),
+        ]
+    });
+    let purpur = jar("Purpur", &mock.url);
+    let mut server = paper_server("migration-purpur");
+    let backups = temp_dir("migration-purpur-backups");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::create_dir_all(server.location.join("world_nether/DIM-1"))?;
+    std::fs::create_dir_all(server.location.join("plugins"))?;
+    std::fs::write(server.location.join("world_nether/level.dat"), b"level")?;
+    std::fs::write(server.location.join("plugins/Core.jar"), b"core")?;
+    std::fs::write(server.location.join("spigot.yml"), b"settings:")?;
+    std::fs::write(server.location.join("Paper-1.20.4.jar"), b"paper")?;
+
+    assert!(!orphans_plugins(&server, &purpur));
+    let report = migrate(&server, &purpur, "1.20.4", "2")?;
+
+    assert_eq!(report.server.jar_name, "Purpur");
+    assert!(report.moved.is_empty());
+    assert!(report.unused_configs.is_empty());
+    assert!(server.location.join("world_nether/DIM-1").is_dir());
+    assert!(server.location.join("spigot.yml").is_file());
+    assert!(!server.location.join("Paper-1.20.4.jar").exists());
+    assert_eq!(
+        std::fs::read(server.location.join("Purpur-1.20.4.jar"))?,
+        b"purpur"
+    );
+    let info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("jar_name = \"Purpur\""));
+    Ok(())
+}
+
+#[test]
+fn failed_downloads_leave_the_worlds_in_place() -> Result<(), Error> {
+    // Knows no jars, every download fails
+    let mock = serve(|_| Vec::new());
+    let vanilla = jar("Vanilla", &mock.url);
+    let mut server = paper_server("migration-failed-download");
+    let backups = temp_dir("migration-failed-download-backups");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::create_dir_all(server.location.join("world_nether/DIM-1"))?;
+    std::fs::write(server.location.join("world_nether/level.dat"), b"level")?;
+    std::fs::write(server.location.join("Paper-1.20.4.jar"), b"paper")?;
+
+    assert!(migrate(&server, &vanilla, "1.20.4", "1").is_err());
+
+    assert!(server.location.join("world_nether/DIM-1").is_dir());
+    assert!(!server.location.join("world/DIM-1").exists());
+    assert_eq!(
+        std::fs::read(server.location.join("Paper-1.20.4.jar"))?,
+        b"paper"
+    );
+    let info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("jar_name = \"Paper\""));
+    let leftovers = std::fs::read_dir(&server.location)?
+        .filter_map(Result::ok)
+        .filter(|e| e.file_name().to_string_lossy().contains("staged"))
+        .count();
+    assert_eq!(leftovers, 0);
+    Ok(())
+}
+
+#[test]
+fn refuses_to_migrate_to_a_proxy() {
+    let server = paper_server("migration-proxy");
+    let velocity = jar("Velocity", "http://127.0.0.1:9");
+
+    assert!(migrate(&server, &velocity, "3.3.0", "1").is_err());
+}
@@ -1,5 +1,98 @@
 use crate::error::Error;
 use crate::minecraft::jars;
+use crate::minecraft::server::Server;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+
+mod backup;
+mod console;
+mod eula;
+mod generator;
+mod http;
+mod instances;
+mod java;
+mod java_install;
+mod jvm;
+mod manifest;
+mod memory;
+mod migration;
+mod network;
+mod player_lists;
+mod plugin_lock;
+mod plugin_sources;
+mod plugin_updates;
+mod plugins;
+mod repository;
+mod server_updates;
+mod templates;
+mod trash;
+
+/// Scratch directory of a test, deleted when dropped.
+pub struct TempDir(PathBuf);
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// Fresh scratch directory for a test
+pub fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!("boxes-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
+
+/// A server living in its own scratch directory, deleted with it.
+pub struct TestServer {
+    pub server: Server,
+    _dir: TempDir,
+}
+
+impl Deref for TestServer {
+    type Target = Server;
+
+    fn deref(&self) -> &Server {
+        &self.server
+    }
+}
+
+impl DerefMut for TestServer {
+    fn deref_mut(&mut self) -> &mut Server {
+        &mut self.server
+    }
+}
+
+// Paper 1.20.4 build 496, registered nowhere
+pub fn paper_server(name: &str) -> TestServer {
+    server_in(temp_dir(name), name, "Paper", "1.20.4", "496")
+}
+
+pub fn server_in(dir: TempDir, name: &str, jar: &str, version: &str, build: &str) -> TestServer {
+    let server = Server::new(
+        name,
+        jar.to_string(),
+        version.to_string(),
+        build.to_string(),
+        &dir,
+    );
+    TestServer { server, _dir: dir }
+}
 
 #[test]
 fn get_jar_returns_expected_jar() -> Result<(), Error> {
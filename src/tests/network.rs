@@ -0,0 +1,203 @@
+use crate::error::Error;
+use crate::minecraft::instances::ports;
+use crate::minecraft::network::{start, wire};
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::tests::{paper_server, server_in, temp_dir, TestServer};
+
+const VELOCITY_TOML: &str = r#"# Config version. Do not change this
+config-version = "2.7"
+
+# What port should the proxy be bound to?
+bind = "0.0.0.0:25577"
+player-info-forwarding-mode = "NONE"
+
+[servers]
+lobby = "127.0.0.1:30066"
+factions = "127.0.0.1:30067"
+try = ["lobby"]
+
+[forced-hosts]
+"lobby.example.com" = ["lobby"]
+"hub.example.com" = ["hub"]
+"#;
+
+fn velocity_server(name: &str) -> Result<TestServer, Error> {
+    let location = temp_dir(name);
+    std::fs::write(location.join("velocity.toml"), VELOCITY_TOML)?;
+    Ok(server_in(
+        location,
+        name,
+        "Velocity",
+        "3.3.0-SNAPSHOT",
+        "400",
+    ))
+}
+
+#[test]
+fn wires_the_proxy_and_its_backends() -> Result<(), Error> {
+    let proxy = velocity_server("network-proxy")?;
+    let hub = paper_server("hub");
+    let arena = paper_server("arena");
+    std::fs::create_dir_all(arena.location.join("config"))?;
+    std::fs::write(
+        arena.location.join("config/paper-global.yml"),
+        "proxies:\n  velocity:\n    enabled: false\n    secret: ''\nchunk-loading-basic:\n  player-max-chunk-load-rate: 100.0\n",
+    )?;
+
+    let wiring = wire(&proxy, &[hub.clone(), arena.clone()], &[25566])?;
+
+    let ports: Vec<u16> = wiring.backend_ports.iter().map(|(_, p)| *p).collect();
+    assert!(!ports.contains(&25566) && !ports.contains(&wiring.proxy_port));
+    assert_ne!(ports[0], ports[1]);
+
+    let text = std::fs::read_to_string(proxy.location.join("velocity.toml"))?;
+    assert!(text.contains("# What port should the proxy be bound to?"));
+    let velocity: toml::Table = toml::from_str(&text)?;
+    assert_eq!(
+        velocity["bind"].as_str(),
+        Some(format!("0.0.0.0:{}", wiring.proxy_port).as_str())
+    );
+    assert_eq!(
+        velocity["player-info-forwarding-mode"].as_str(),
+        Some("modern")
+    );
+    let servers = velocity["servers"].as_table().unwrap();
+    assert_eq!(
+        servers["hub"].as_str(),
+        Some(format!("127.0.0.1:{}", ports[0]).as_str())
+    );
+    assert!(!servers.contains_key("lobby"));
+    assert_eq!(
+        servers["try"].as_array().unwrap(),
+        &vec![toml::Value::from("hub"), toml::Value::from("arena")]
+    );
+    let forced = velocity["forced-hosts"].as_table().unwrap();
+    assert_eq!(forced.keys().collect::<Vec<_>>(), ["hub.example.com"]);
+
+    let secret = std::fs::read_to_string(proxy.location.join("forwarding.secret"))?;
+    assert_eq!(secret.len(), 24);
+    for (backend, port) in [(hub.clone(), ports[0]), (arena.clone(), ports[1])] {
+        let global: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(
+            backend.location.join("config/paper-global.yml"),
+        )?)?;
+        assert_eq!(
+            global["proxies"]["velocity"]["enabled"].as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            global["proxies"]["velocity"]["secret"].as_str(),
+            Some(secret.as_str())
+        );
+        let manipulator = ServerManipulator { server: backend };
+        assert_eq!(
+            manipulator.get_property("online-mode").as_deref(),
+            Some("false")
+        );
+        assert_eq!(
+            manipulator.get_property("server-port"),
+            Some(port.to_string())
+        );
+    }
+    let arena_global = std::fs::read_to_string(arena.location.join("config/paper-global.yml"))?;
+    assert!(arena_global.contains("player-max-chunk-load-rate"));
+    Ok(())
+}
+
+#[test]
+fn keeps_the_forwarding_secret_when_rewiring() -> Result<(), Error> {
+    let proxy = velocity_server("network-rewire")?;
+    let backend = paper_server("network-rewire-backend");
+    let backends = [backend.clone()];
+    wire(&proxy, &backends, &[])?;
+    let secret = std::fs::read_to_string(proxy.location.join("forwarding.secret"))?;
+
+    wire(&proxy, &backends, &[])?;
+
+    assert_eq!(
+        std::fs::read_to_string(proxy.location.join("forwarding.secret"))?,
+        secret
+    );
+    Ok(())
+}
+
+#[test]
+fn refuses_a_proxy_that_is_not_velocity() {
+    let not_proxy = paper_server("network-not-proxy");
+    let backend = paper_server("network-not-proxy-backend");
+
+    assert!(matches!(
+        wire(&not_proxy, std::slice::from_ref(&backend.server), &[]),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn wires_backends_older_than_1_19_through_paper_yml() -> Result<(), Error> {
+    let proxy = velocity_server("network-legacy")?;
+    let location = temp_dir("network-legacy-backend");
+    std::fs::write(
+        location.join("paper.yml"),
+        "settings:\n  velocity-support:\n    enabled: false\n  bungee-online-mode: true\n",
+    )?;
+    let backend = Server::new(
+        "legacy",
+        "Paper".to_string(),
+        "1.16.5".to_string(),
+        "794".to_string(),
+        &location,
+    );
+
+    wire(&proxy, &[backend], &[])?;
+
+    let paper: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(location.join("paper.yml"))?)?;
+    let velocity = &paper["settings"]["velocity-support"];
+    assert_eq!(velocity["enabled"].as_bool(), Some(true));
+    assert_eq!(velocity["secret"].as_str().map(str::len), Some(24));
+    assert_eq!(
+        paper["settings"]["bungee-online-mode"].as_bool(),
+        Some(true)
+    );
+    assert!(!location.join("config/paper-global.yml").exists());
+    Ok(())
+}
+
+#[test]
+fn refuses_backends_without_modern_forwarding() -> Result<(), Error> {
+    let proxy = velocity_server("network-ancient")?;
+    let backend = server_in(
+        temp_dir("network-ancient-backend"),
+        "ancient",
+        "Paper",
+        "1.12.2",
+        "1620",
+    );
+
+    assert!(matches!(
+        wire(&proxy, std::slice::from_ref(&backend.server), &[]),
+        Err(Error::InvalidInput(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn proxies_report_their_bind_port() -> Result<(), Error> {
+    let proxy = velocity_server("network-bind")?;
+
+    assert!(ports(&proxy).contains(&25577));
+    Ok(())
+}
+
+#[test]
+fn refuses_to_start_backends_without_the_eula() -> Result<(), Error> {
+    let proxy = velocity_server("network-eula-proxy")?;
+    let hub = paper_server("network-eula-hub");
+
+    let result = start(&proxy, std::slice::from_ref(&hub.server), false);
+
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+    assert!(!hub.location.join("logs/boxes.log").exists());
+    assert!(!proxy.location.join("logs/boxes.log").exists());
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::minecraft::player_lists::{plan_sync, PlayerList, SyncMode};
+use serde_json::{json, Value};
+
+fn op(uuid: &str, name: &str, level: u8) -> Value {
+    json!({ "uuid": uuid, "name": name, "level": level, "bypassesPlayerLimit": false })
+}
+
+#[test]
+fn merge_keeps_target_entries_and_reports_conflicts() {
+    let source = vec![op("a", "Alice", 4), op("b", "Bob", 4)];
+    let target = vec![op("b", "Bob", 2), op("c", "Carol", 4)];
+    let (entries, report) = plan_sync(PlayerList::Ops, SyncMode::Merge, "lobby", &source, &target);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(report.added, vec!["Alice (a)"]);
+    assert!(report.removed.is_empty());
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].fields, vec!["level"]);
+    assert!(entries.contains(&op("b", "Bob", 4)));
+}
+
+#[test]
+fn mirror_removes_entries_missing_from_source() {
+    let source = vec![op("a", "Alice", 4)];
+    let target = vec![op("a", "Alice", 4), op("c", "Carol", 4)];
+    let (entries, report) = plan_sync(PlayerList::Ops, SyncMode::Mirror, "lobby", &source, &target);
+    assert_eq!(entries, source);
+    assert_eq!(report.removed, vec!["Carol (c)"]);
+    assert!(report.conflicts.is_empty());
+}
+
+#[test]
+fn banned_ips_are_matched_by_ip() {
+    let source = vec![json!({ "ip": "10.0.0.1", "reason": "Spam" })];
+    let target = vec![json!({ "ip": "10.0.0.1", "reason": "Spam" })];
+    let (_, report) = plan_sync(
+        PlayerList::BannedIps,
+        SyncMode::Merge,
+        "lobby",
+        &source,
+        &target,
+    );
+    assert!(report.is_empty());
+}
+
+#[test]
+fn ban_timestamps_are_not_conflicts() {
+    let ban = |created: &str, expires: &str| {
+        json!({
+            "uuid": "a",
+            "name": "Alice",
+            "created": created,
+            "source": "Server",
+            "expires": expires,
+            "reason": "Griefing"
+        })
+    };
+    let source = vec![ban("2024-01-01 10:00:00 +0000", "forever")];
+    let target = vec![ban(
+        "2024-02-03 18:30:00 +0000",
+        "2025-01-01 00:00:00 +0000",
+    )];
+    let (_, report) = plan_sync(
+        PlayerList::BannedPlayers,
+        SyncMode::Merge,
+        "lobby",
+        &source,
+        &target,
+    );
+    assert!(report.is_empty());
+}
@@ -0,0 +1,53 @@
+use crate::error::Error;
+use crate::minecraft::plugin_lock::PluginLock;
+use crate::minecraft::plugin_sources::{install, PluginApis, PluginSource};
+use crate::tests::paper_server;
+use crate::tests::plugin_sources::{modrinth, CORE_ID, LIB_ID};
+use crate::utils::hash_bytes;
+
+#[test]
+fn install_records_plugins_in_lockfile() -> Result<(), Error> {
+    let core_jar: &[u8] = b"core jar";
+    let api = modrinth(core_jar, hash_bytes("sha512", core_jar)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let server = paper_server("lock-install");
+
+    install(&server, "core", Some(PluginSource::Modrinth), &apis)?;
+
+    let lock = PluginLock::load(&server)?;
+    let core = lock.get(CORE_ID).unwrap();
+    assert_eq!(core.slug.as_deref(), Some("core"));
+    assert_eq!(core.version, "1.0.0");
+    assert_eq!(core.file_name, "core.jar");
+    assert_eq!(core.hash, hash_bytes("sha512", core_jar)?);
+    assert!(lock.get(LIB_ID).is_some());
+    Ok(())
+}
+
+#[test]
+fn sync_restores_locked_plugins_and_reports_unmanaged_jars() -> Result<(), Error> {
+    let core_jar: &[u8] = b"core jar";
+    let api = modrinth(core_jar, hash_bytes("sha512", core_jar)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let source = paper_server("lock-source");
+    install(&source, "core", Some(PluginSource::Modrinth), &apis)?;
+
+    let target = paper_server("lock-target");
+    let plugins = target.location.join("plugins");
+    std::fs::create_dir_all(&plugins)?;
+    std::fs::write(plugins.join("core.jar"), b"tampered")?;
+    std::fs::write(plugins.join("Manual.jar"), b"manual")?;
+    let lock = PluginLock::load(&source)?;
+
+    let report = lock.sync(&target, &apis)?;
+
+    assert_eq!(report.installed.len(), 2);
+    assert_eq!(report.unmanaged, vec!["Manual.jar"]);
+    assert_eq!(std::fs::read(plugins.join("core.jar"))?, core_jar);
+    assert!(plugins.join("Manual.jar").exists());
+
+    let report = lock.sync(&target, &apis)?;
+    assert!(report.installed.is_empty());
+    assert_eq!(report.up_to_date.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use crate::error::Error;
+use crate::minecraft::plugin_sources::{install, parse_spec, PluginApis, PluginSource};
+use crate::tests::http::{serve, MockServer};
+use crate::tests::paper_server;
+use crate::utils::hash_bytes;
+
+fn modrinth_version(url: &str, slug: &str, id: &str, hash: &str, deps: &str) -> String {
+    let hash = hash.trim_start_matches("sha512:");
+    format!(
+        r#"{{"id":"v-{slug}","project_id":"{id}","version_number":"1.0.0","files":[{{"hashes":{{"sha512":"{hash}"}},"url":"{url}/files/{slug}.jar","filename":"{slug}.jar","primary":true}}],"dependencies":[{deps}]}}"#
+    )
+}
+
+pub const OLD_CORE_JAR: &[u8] = b"old core jar";
+// Modrinth ids are random, unlike the slugs users type
+pub const CORE_ID: &str = "AANobbMI";
+pub const LIB_ID: &str = "P7dR8mSH";
+
+// A Modrinth stand-in with `core`, which requires `lib`
+pub fn modrinth(core_jar: &'static [u8], core_hash: String) -> MockServer {
+    let lib_jar: &[u8] = b"lib jar";
+    let lib_hash = hash_bytes("sha512", lib_jar).unwrap();
+    serve(move |url| {
+        let core = modrinth_version(
+            url,
+            "core",
+            CORE_ID,
+            &core_hash,
+            &format!(
+                r#"{{"project_id":"{LIB_ID}","version_id":null,"dependency_type":"required"}},{{"project_id":"extra","version_id":null,"dependency_type":"optional"}}"#
+            ),
+        );
+        let lib = modrinth_version(url, "lib", LIB_ID, &lib_hash, "");
+        let mut routes = Vec::new();
+        for (slug, id, version) in [("core", CORE_ID, core), ("lib", LIB_ID, lib)] {
+            let project = format!(
+                r#"{{"id":"{id}","slug":"{slug}","title":"{}"}}"#,
+                slug.to_uppercase()
+            );
+            // Projects are found by slug or id, their versions by id
+            routes.push((format!("/v2/project/{slug}"), project.clone().into_bytes()));
+            routes.push((format!("/v2/project/{id}"), project.into_bytes()));
+            routes.push((
+                format!("/v2/project/{id}/version"),
+                format!("[{version}]").into_bytes(),
+            ));
+            routes.push((
+                format!("/v2/project/{id}/version/1.0.0"),
+                version.into_bytes(),
+            ));
+        }
+        // Hash lookups know an older release of core
+        let old_hash = hash_bytes("sha512", OLD_CORE_JAR).unwrap();
+        routes.push((
+            "/v2/version_files".to_string(),
+            format!(
+                r#"{{"{}":{{"project_id":"{CORE_ID}","version_number":"0.9.0","files":[],"dependencies":[]}}}}"#,
+                old_hash.trim_start_matches("sha512:")
+            )
+            .into_bytes(),
+        ));
+        routes.push(("/files/core.jar".to_string(), core_jar.to_vec()));
+        routes.push(("/files/lib.jar".to_string(), lib_jar.to_vec()));
+        routes
+    })
+}
+
+#[test]
+fn parses_plugin_specs() {
+    assert_eq!(parse_spec("luckperms"), ("luckperms", None));
+    assert_eq!(
+        parse_spec("luckperms@5.4.102"),
+        ("luckperms", Some("5.4.102"))
+    );
+    assert_eq!(parse_spec("luckperms@"), ("luckperms", None));
+}
+
+#[test]
+fn installs_plugin_with_required_dependencies() -> Result<(), Error> {
+    let core_jar: &[u8] = b"core jar";
+    let api = modrinth(core_jar, hash_bytes("sha512", core_jar)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let server = paper_server("install-deps");
+
+    let installed = install(&server, "core", Some(PluginSource::Modrinth), &apis)?;
+
+    assert_eq!(installed.len(), 2);
+    let plugins = server.location.join("plugins");
+    assert_eq!(std::fs::read(plugins.join("core.jar"))?, core_jar);
+    assert!(plugins.join("lib.jar").exists());
+    let requests = api.requests.lock().unwrap();
+    assert!(requests.iter().any(
+        |r| r.starts_with(&format!("/v2/project/{CORE_ID}/version?"))
+            && r.contains("paper")
+            && r.contains("1.20.4")
+    ));
+    Ok(())
+}
+
+#[test]
+fn rejects_downloads_with_wrong_hash() -> Result<(), Error> {
+    let api = modrinth(b"core jar", hash_bytes("sha512", b"something else")?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let server = paper_server("install-bad-hash");
+
+    let result = install(&server, "core", Some(PluginSource::Modrinth), &apis);
+
+    assert!(matches!(result, Err(Error::HashMismatch { .. })));
+    assert!(!server.location.join("plugins").join("core.jar").exists());
+    Ok(())
+}
+
+#[test]
+fn falls_back_to_hangar() -> Result<(), Error> {
+    let jar: &[u8] = b"hangar jar";
+    let hash = hash_bytes("sha256", jar)?;
+    let hash = hash.trim_start_matches("sha256:").to_string();
+    let api = serve(move |url| {
+        vec![
+            (
+                "/hangar/projects/ViaVersion".to_string(),
+                br#"{"name":"ViaVersion","namespace":{"owner":"ViaVersion","slug":"ViaVersion"}}"#
+                    .to_vec(),
+            ),
+            (
+                "/hangar/projects/ViaVersion/versions".to_string(),
+                format!(
+                    r#"{{"result":[{{"name":"4.9.2","downloads":{{"PAPER":{{"fileInfo":{{"name":"ViaVersion-4.9.2.jar","sizeBytes":10,"sha256Hash":"{hash}"}},"externalUrl":null,"downloadUrl":"{url}/files/via.jar"}}}},"pluginDependencies":{{}}}}]}}"#
+                )
+                .into_bytes(),
+            ),
+            ("/files/via.jar".to_string(), jar.to_vec()),
+        ]
+    });
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let server = paper_server("install-hangar");
+
+    let installed = install(&server, "ViaVersion", None, &apis)?;
+
+    assert_eq!(installed[0].source, PluginSource::Hangar);
+    assert!(server
+        .location
+        .join("plugins")
+        .join("ViaVersion-4.9.2.jar")
+        .exists());
+    Ok(())
+}
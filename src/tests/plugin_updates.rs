@@ -0,0 +1,76 @@
+use crate::error::Error;
+use crate::minecraft::plugin_lock::PluginLock;
+use crate::minecraft::plugin_sources::{PluginApis, PluginRelease, PluginSource};
+use crate::minecraft::plugin_updates::{check, upgrade, PluginUpdate};
+use crate::tests::paper_server;
+use crate::tests::plugin_sources::{modrinth, CORE_ID, OLD_CORE_JAR};
+use crate::utils::hash_bytes;
+
+#[test]
+fn finds_and_upgrades_plugins_identified_by_hash() -> Result<(), Error> {
+    let core_jar: &[u8] = b"core jar";
+    let api = modrinth(core_jar, hash_bytes("sha512", core_jar)?);
+    let apis = PluginApis::new(&format!("{}/v2", api.url), &format!("{}/hangar", api.url))?;
+    let server = paper_server("upgrade-by-hash");
+    let plugins = server.location.join("plugins");
+    std::fs::create_dir_all(&plugins)?;
+    std::fs::write(plugins.join("Core-0.9.0.jar"), OLD_CORE_JAR)?;
+    std::fs::write(plugins.join("Unknown.jar"), b"unknown")?;
+
+    let updates = check(&server, &apis)?;
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].current, "0.9.0");
+    assert_eq!(updates[0].latest.version, "1.0.0");
+    assert!(updates[0].is_outdated());
+
+    let rollback = upgrade(&server, &[&updates[0]], &apis)?;
+
+    assert!(!plugins.join("Core-0.9.0.jar").exists());
+    assert_eq!(std::fs::read(plugins.join("core.jar"))?, core_jar);
+    assert_eq!(
+        std::fs::read(rollback.join("Core-0.9.0.jar"))?,
+        OLD_CORE_JAR
+    );
+    let lock = PluginLock::load(&server)?;
+    assert_eq!(lock.get(CORE_ID).unwrap().version, "1.0.0");
+    assert!(!check(&server, &apis)?[0].is_outdated());
+    Ok(())
+}
+
+#[test]
+fn newer_local_builds_are_not_outdated() {
+    assert!(update("1.9.2", "1.10.0").is_outdated());
+    assert!(!update("1.10.0", "1.10.0").is_outdated());
+    assert!(!update("1.11.0-SNAPSHOT", "1.10.0").is_outdated());
+}
+
+#[test]
+fn prefixes_and_tags_are_ignored_when_comparing_versions() {
+    assert!(update("v5.4.101", "5.4.102-bukkit").is_outdated());
+    assert!(!update("5.4.102-bukkit", "v5.4.102").is_outdated());
+    assert!(update("v5.4-SNAPSHOT-b12", "V5.4.1").is_outdated());
+    assert!(update("release", "latest").is_outdated());
+    assert!(!update("latest", "latest").is_outdated());
+}
+
+fn update(current: &str, latest: &str) -> PluginUpdate {
+    PluginUpdate {
+        file_name: "Core.jar".to_string(),
+        name: "Core".to_string(),
+        source: PluginSource::Modrinth,
+        project_id: "core".to_string(),
+        current: current.to_string(),
+        latest: PluginRelease {
+            source: PluginSource::Modrinth,
+            project_id: "core".to_string(),
+            slug: "core".to_string(),
+            name: "Core".to_string(),
+            version: latest.to_string(),
+            file_name: "core.jar".to_string(),
+            url: String::new(),
+            hash: String::new(),
+            dependencies: Vec::new(),
+        },
+    }
+}
@@ -0,0 +1,140 @@
+use crate::error::Error;
+use crate::minecraft::console;
+use crate::minecraft::plugins::{self, find_issues, PluginInfo, PluginIssue};
+use crate::minecraft::server::{is_running, Server};
+use crate::tests::temp_dir;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub fn write_jar(dir: &Path, file: &str, descriptor: &str, contents: &str) -> PathBuf {
+    let path = dir.join(file);
+    let mut jar = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    jar.start_file(descriptor, zip::write::SimpleFileOptions::default())
+        .unwrap();
+    jar.write_all(contents.as_bytes()).unwrap();
+    jar.finish().unwrap();
+    path
+}
+
+#[test]
+fn reads_plugin_yml_metadata() -> Result<(), Error> {
+    let dir = temp_dir("plugin-yml");
+    let jar = write_jar(
+        &dir,
+        "Essentials.jar",
+        "plugin.yml",
+        "name: Essentials\nversion: 2.10\nauthor: md_5\nauthors: [snowleo]\napi-version: 1.20\nmain: com.earth2me.essentials.Essentials\ndepend: [Vault]\nsoftdepend: [LuckPerms]\n",
+    );
+    let info = PluginInfo::read(&jar)?;
+    assert_eq!(info.name, "Essentials");
+    assert_eq!(info.version, "2.10");
+    assert_eq!(info.api_version.as_deref(), Some("1.20"));
+    assert_eq!(info.authors, vec!["md_5", "snowleo"]);
+    assert_eq!(info.depend, vec!["Vault"]);
+    assert_eq!(info.softdepend, vec!["LuckPerms"]);
+    Ok(())
+}
+
+#[test]
+fn reads_velocity_plugin_json() -> Result<(), Error> {
+    let dir = temp_dir("velocity-json");
+    let jar = write_jar(
+        &dir,
+        "proxy.jar",
+        "velocity-plugin.json",
+        r#"{"id":"proxyplugin","name":"ProxyPlugin","version":"1.0.0","authors":["Azuyamat"],"main":"com.example.Main","dependencies":[{"id":"luckperms","optional":false},{"id":"spark","optional":true}]}"#,
+    );
+    let info = PluginInfo::read(&jar)?;
+    assert_eq!(info.name, "ProxyPlugin");
+    assert_eq!(info.depend, vec!["luckperms"]);
+    assert_eq!(info.softdepend, vec!["spark"]);
+    Ok(())
+}
+
+#[test]
+fn finds_missing_duplicate_and_too_new_plugins() {
+    let plugin = |name: &str, version: &str, api: &str, depend: &[&str]| PluginInfo {
+        file: format!("{name}-{version}.jar"),
+        name: name.to_string(),
+        version: version.to_string(),
+        api_version: Some(api.to_string()),
+        depend: depend.iter().map(ToString::to_string).collect(),
+        ..PluginInfo::default()
+    };
+    let plugins = vec![
+        plugin("Essentials", "2.20", "1.13", &["Vault"]),
+        plugin("WorldEdit", "7.2", "1.20", &[]),
+        plugin("WorldEdit", "7.3", "1.21", &[]),
+    ];
+    let issues = find_issues(&plugins, "1.20.4");
+    assert_eq!(issues.len(), 3);
+    assert!(issues.iter().any(|i| matches!(i,
+        PluginIssue::MissingDependency { dependency, .. } if dependency == "Vault")));
+    assert!(issues.iter().any(|i| matches!(i,
+        PluginIssue::ApiTooNew { api_version, .. } if api_version == "1.21")));
+    assert!(issues.iter().any(|i| matches!(i,
+        PluginIssue::Duplicate { files, .. } if files.len() == 2)));
+}
+
+#[test]
+fn disables_enables_and_removes_plugins_by_name() -> Result<(), Error> {
+    let dir = temp_dir("plugin-toggle");
+    let server = Server::new(
+        "plugin-toggle",
+        "Paper".to_string(),
+        "1.20.4".to_string(),
+        "496".to_string(),
+        &dir,
+    );
+    let plugins_dir = dir.join("plugins");
+    std::fs::create_dir_all(&plugins_dir)?;
+    write_jar(
+        &plugins_dir,
+        "vault-1.7.jar",
+        "plugin.yml",
+        "name: Vault\nversion: 1.7\n",
+    );
+
+    assert_eq!(plugins::disable(&server, "vault")?, "vault-1.7.jar");
+    assert!(plugins_dir.join(".disabled").join("vault-1.7.jar").exists());
+    assert!(server.plugins().is_empty());
+
+    assert_eq!(plugins::enable(&server, "Vault")?, "vault-1.7.jar");
+    assert!(plugins_dir.join("vault-1.7.jar").exists());
+
+    plugins::disable(&server, "vault-1.7")?;
+    assert_eq!(plugins::remove(&server, "vault")?, "vault-1.7.jar");
+    assert!(!plugins_dir.join(".disabled").join("vault-1.7.jar").exists());
+    assert!(plugins::remove(&server, "vault").is_err());
+    Ok(())
+}
+
+#[test]
+fn running_servers_are_matched_by_exact_name() {
+    let jvms = "1234 paper.jar -Dname=lobby2 -Xmx2G\n5678 Jps -Dapplication.home=/usr";
+    assert!(is_running(jvms, "lobby2"));
+    assert!(!is_running(jvms, "lobby"));
+}
+
+#[test]
+fn running_servers_with_spaces_in_their_name_are_found() {
+    let jvms = "1234 paper.jar -Dname=my lobby -Xms1G -Xmx2G\n5678 Jps -Dapplication.home=/usr";
+    assert!(is_running(jvms, "my lobby"));
+    assert!(!is_running(jvms, "my"));
+    assert!(!is_running(jvms, "lobby"));
+}
+
+#[test]
+fn servers_are_running_while_their_console_bridge_is_open() -> Result<(), Error> {
+    let dir = temp_dir("bridge-running");
+    assert!(!console::bridge_is_open(&dir));
+
+    let listener = std::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    std::fs::write(dir.join(console::BRIDGE_FILE), format!("{port} token"))?;
+    assert!(console::bridge_is_open(&dir));
+
+    drop(listener);
+    assert!(!console::bridge_is_open(&dir));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::error::Error;
+use crate::minecraft::repository::{Chunker, Repository, MAX_CHUNK, MIN_CHUNK};
+use crate::tests::temp_dir;
+use std::collections::HashSet;
+
+// Incompressible, reproducible test data
+pub fn noise(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+fn chunks(data: &[u8]) -> Vec<Vec<u8>> {
+    Chunker::new(data).map(Result::unwrap).collect()
+}
+
+#[test]
+fn chunks_are_bounded_and_cover_the_input() {
+    let data = noise(1, 3 * 1024 * 1024);
+    let chunks = chunks(&data);
+
+    assert_eq!(chunks.concat(), data);
+    assert!(chunks.len() > 5);
+    for chunk in &chunks[..chunks.len() - 1] {
+        assert!(chunk.len() >= MIN_CHUNK && chunk.len() <= MAX_CHUNK);
+    }
+}
+
+#[test]
+fn an_insertion_only_changes_nearby_chunks() {
+    let data = noise(2, 2 * 1024 * 1024);
+    let mut edited = data.clone();
+    edited.splice(1000..1000, *b"a few new bytes");
+
+    let before: HashSet<Vec<u8>> = chunks(&data).into_iter().collect();
+    let after = chunks(&edited);
+    let changed = after.iter().filter(|c| !before.contains(*c)).count();
+
+    assert!(changed <= 2, "{changed} of {} chunks changed", after.len());
+}
+
+#[test]
+fn stores_chunks_once_and_collects_garbage() -> Result<(), Error> {
+    let store = temp_dir("repository-store");
+    let repository = Repository::open(&store)?;
+    let data = noise(3, 100_000);
+
+    let (hash, added) = repository.store(&data)?;
+    assert!(added > 0);
+    assert_eq!(repository.store(&data)?, (hash.clone(), 0));
+    assert_eq!(repository.load(&hash)?, data);
+
+    let (other, _) = repository.store(b"other chunk")?;
+    repository.collect_garbage(&HashSet::from([hash.as_str()]))?;
+    assert!(repository.load(&hash).is_ok());
+    assert!(repository.load(&other).is_err());
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use crate::error::Error;
+use crate::minecraft::backup;
+use crate::minecraft::jars::Jar;
+use crate::minecraft::server_updates::{check, is_downgrade, resolve, upgrade};
+use crate::tests::http::{serve, MockServer};
+use crate::tests::paper_server;
+use crate::tests::temp_dir;
+
+// A Paper API stand-in with builds 1 to 3 of 1.20.6
+fn paper_api() -> (MockServer, Jar) {
+    let mock = serve(|_| {
+        vec![
+            (
+                "/paper/1.20.6".to_string(),
+                br#"{"project_id":"paper","project_name":"Paper","version":"1.20.6","builds":[1,2,3]}"#
+                    .to_vec(),
+            ),
+            ("/paper/1.20.6/3.jar".to_string(), b"paper 1.20.6".to_vec()),
+            (
+                "/paper/1.20.6/builds".to_string(),
+                br#"{"builds":[
+                    {"build":1,"changes":[]},
+                    {"build":2,"changes":[{"commit":"a1b2c3d4e5f6","summary":"Fix chunk loading","message":"Fix chunk loading\n\nDetails"}]},
+                    {"build":3,"changes":[{"commit":"f6e5d4c3b2a1","summary":"Update upstream","message":"Update upstream"}]}
+                ]}"#
+                .to_vec(),
+            ),
+        ]
+    });
+    let jar = Jar {
+        name: "Paper".to_string(),
+        download_url: format!("{}/paper/{{version}}/{{build}}.jar", mock.url),
+        builds_url: format!("{}/paper/{{version}}", mock.url),
+        versions_url: format!("{}/paper", mock.url),
+        changelog_url: Some(format!("{}/paper/{{version}}/builds", mock.url)),
+    };
+    (mock, jar)
+}
+
+#[test]
+fn resolves_the_latest_build() -> Result<(), Error> {
+    let (_mock, jar) = paper_api();
+    let server = paper_server("upgrade-resolve");
+
+    let latest = resolve(&jar, &server, Some("1.20.6"), Some("latest"))?;
+    let pinned = resolve(&jar, &server, None, Some("500"))?;
+
+    assert_eq!(latest, ("1.20.6".to_string(), "3".to_string()));
+    assert_eq!(pinned, ("1.20.4".to_string(), "500".to_string()));
+    Ok(())
+}
+
+#[test]
+fn detects_downgrades() {
+    let server = paper_server("upgrade-downgrade");
+
+    assert!(is_downgrade(&server, "1.20.1"));
+    assert!(is_downgrade(&server, "1.19.4"));
+    assert!(!is_downgrade(&server, "1.20.4"));
+    assert!(!is_downgrade(&server, "1.21"));
+}
+
+#[test]
+fn upgrades_the_jar_after_a_backup() -> Result<(), Error> {
+    let (_mock, jar) = paper_api();
+    let mut server = paper_server("upgrade-jar");
+    let backups = temp_dir("upgrade-jar-backups");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::create_dir_all(server.location.join("world"))?;
+    std::fs::write(server.location.join("world/level.dat"), b"level")?;
+    std::fs::write(server.location.join("Paper-1.20.4.jar"), b"paper 1.20.4")?;
+
+    let (upgraded, backup) = upgrade(&server, &jar, "1.20.6", "3")?;
+
+    assert_eq!(upgraded.version, "1.20.6");
+    assert_eq!(upgraded.build, "3");
+    assert!(!server.location.join("Paper-1.20.4.jar").exists());
+    assert_eq!(
+        std::fs::read(server.location.join("Paper-1.20.6.jar"))?,
+        b"paper 1.20.6"
+    );
+    let info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("version = \"1.20.6\""));
+    assert_eq!(backup::list(&server)?[0].id, backup.id);
+    Ok(())
+}
+
+#[test]
+fn keeps_the_old_jar_when_the_download_fails() -> Result<(), Error> {
+    let (_mock, jar) = paper_api();
+    let mut server = paper_server("upgrade-failed");
+    let backups = temp_dir("upgrade-failed-backups");
+    server.backup.directory = Some(backups.to_path_buf());
+    std::fs::write(server.location.join("Paper-1.20.4.jar"), b"paper 1.20.4")?;
+
+    let result = upgrade(&server, &jar, "1.20.6", "99");
+
+    assert!(result.is_err());
+    assert!(server.location.join("Paper-1.20.4.jar").exists());
+    assert!(!server.location.join("Paper-1.20.6.jar").exists());
+    let info = std::fs::read_to_string(server.location.join("server_box.toml"))?;
+    assert!(info.contains("version = \"1.20.4\""));
+    Ok(())
+}
+
+#[test]
+fn reports_builds_behind_with_their_changes() -> Result<(), Error> {
+    let (_mock, jar) = paper_api();
+    let mut server = paper_server("outdated-paper");
+    server.version = "1.20.6".to_string();
+    server.build = "1".to_string();
+
+    let status = check(&server, &jar)?;
+
+    assert!(status.is_outdated());
+    assert_eq!(status.behind, Some(2));
+    assert_eq!(status.latest_build, Some(3));
+    let builds: Vec<u32> = status.changes.iter().map(|c| c.build).collect();
+    assert_eq!(builds, vec![3, 2]);
+    assert_eq!(status.changes[1].changes, vec!["a1b2c3d Fix chunk loading"]);
+    let json = serde_json::to_value(&status)?;
+    assert_eq!(json["behind"], 2);
+    Ok(())
+}
+
+#[test]
+fn reports_builds_behind_without_a_changelog() -> Result<(), Error> {
+    let (mock, mut jar) = paper_api();
+    jar.changelog_url = Some(format!("{}/paper/{{version}}/missing", mock.url));
+    let mut server = paper_server("outdated-paper-no-changelog");
+    server.version = "1.20.6".to_string();
+    server.build = "1".to_string();
+
+    let status = check(&server, &jar)?;
+
+    assert_eq!(status.behind, Some(2));
+    assert!(status.changes.is_empty());
+    Ok(())
+}
+
+#[test]
+fn reads_purpur_changes_per_build() -> Result<(), Error> {
+    let mock = serve(|_| {
+        vec![
+            (
+                "/purpur/1.20.4".to_string(),
+                br#"{"project":"purpur","version":"1.20.4","builds":{"latest":"11","all":["10","11"]}}"#
+                    .to_vec(),
+            ),
+            (
+                "/purpur/1.20.4/11".to_string(),
+                br#"{"build":"11","commits":[{"author":"dev","description":"Add option\n\nMore text","hash":"0123456789ab","timestamp":0}]}"#
+                    .to_vec(),
+            ),
+        ]
+    });
+    let jar = Jar {
+        name: "Purpur".to_string(),
+        download_url: format!("{}/purpur/{{version}}/{{build}}/download", mock.url),
+        builds_url: format!("{}/purpur/{{version}}", mock.url),
+        versions_url: format!("{}/purpur", mock.url),
+        changelog_url: Some(format!("{}/purpur/{{version}}/{{build}}", mock.url)),
+    };
+    let mut server = paper_server("outdated-purpur");
+    server.jar_name = "Purpur".to_string();
+    server.build = "10".to_string();
+
+    let status = check(&server, &jar)?;
+
+    assert_eq!(status.behind, Some(1));
+    assert_eq!(status.changes[0].changes, vec!["0123456 Add option"]);
+    Ok(())
+}
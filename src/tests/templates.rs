@@ -0,0 +1,137 @@
+use crate::error::Error;
+use crate::minecraft::server::Server;
+use crate::minecraft::server_manipulator::ServerManipulator;
+use crate::minecraft::templates::Templates;
+use crate::tests::{paper_server, temp_dir, TestServer};
+
+// A minigame backend with a world, plugin configs and tuned settings
+fn minigame_server(name: &str) -> Result<TestServer, Error> {
+    let mut server = paper_server(name);
+    server.xmx = Some("4G".to_string());
+    server.tags = vec!["minigame".to_string()];
+    // Only recorded, nothing is backed up
+    server.backup.directory = Some(server.location.join("backups"));
+    server.backup.keep_last = Some(3);
+    server.write();
+    for dir in [
+        "world",
+        "plugins/Arena",
+        "plugins/.rollback/20240101-120000",
+        "plugins/.disabled",
+        "logs",
+    ] {
+        std::fs::create_dir_all(server.location.join(dir))?;
+    }
+    std::fs::write(server.location.join("world/level.dat"), b"level")?;
+    std::fs::write(
+        server.location.join("plugins/Arena/config.yml"),
+        b"rounds: 3",
+    )?;
+    std::fs::write(server.location.join("logs/latest.log"), b"log")?;
+    std::fs::write(
+        server
+            .location
+            .join("plugins/.rollback/20240101-120000/Arena.jar"),
+        b"old arena",
+    )?;
+    std::fs::write(server.location.join("plugins/.disabled/Chat.jar"), b"chat")?;
+    std::fs::write(server.location.join("Paper-1.20.4.jar"), b"paper")?;
+    std::fs::write(
+        server.location.join("server.properties"),
+        "# Minecraft server properties\nmotd=Minigames\nserver-port=25570\nallow-nether=false\n",
+    )?;
+    Ok(server)
+}
+
+#[test]
+fn creates_a_template_from_a_server() -> Result<(), Error> {
+    let server = minigame_server("template-source")?;
+    let store = temp_dir("template-store");
+    let templates = Templates::at(&store)?;
+
+    let template = templates.create_from(&server, "minigame")?;
+
+    let files = templates.files("minigame")?;
+    assert!(files.join("plugins/Arena/config.yml").is_file());
+    for generated in [
+        "world",
+        "logs",
+        "Paper-1.20.4.jar",
+        "server_box.toml",
+        "plugins/.rollback",
+        "plugins/.disabled",
+    ] {
+        assert!(!files.join(generated).exists(), "{generated} was copied");
+    }
+    assert_eq!(
+        template.server.get("xmx").and_then(|v| v.as_str()),
+        Some("4G")
+    );
+    assert!(!template.server.contains_key("server_name"));
+    let backup = template.server["backup"].as_table().unwrap();
+    assert!(!backup.contains_key("directory"));
+    assert_eq!(
+        backup.get("keep_last").and_then(toml::Value::as_integer),
+        Some(3)
+    );
+    assert_eq!(
+        template.properties.get("motd").map(String::as_str),
+        Some("Minigames")
+    );
+    assert!(!template.properties.contains_key("server-port"));
+    assert_eq!(templates.list()?.len(), 1);
+    assert!(matches!(
+        templates.create_from(&server, "minigame"),
+        Err(Error::InvalidInput(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn lays_a_template_down_in_a_new_server() -> Result<(), Error> {
+    let source = minigame_server("template-apply-source")?;
+    let store = temp_dir("template-apply-store");
+    let templates = Templates::at(&store)?;
+    let template = templates.create_from(&source, "minigame")?;
+    let server = paper_server("template-apply-target");
+
+    let applied = templates.apply(&template, &server)?;
+
+    assert_eq!(applied.server_name, "template-apply-target");
+    assert_eq!(applied.location, server.location);
+    assert_eq!(applied.xmx.as_deref(), Some("4G"));
+    assert_eq!(applied.tags, ["minigame"]);
+    assert_eq!(applied.backup.directory, None);
+    assert_eq!(applied.backup.keep_last, Some(3));
+    assert_eq!(
+        std::fs::read(server.location.join("plugins/Arena/config.yml"))?,
+        b"rounds: 3"
+    );
+    let reloaded = Server::from_path(&server.location.to_string_lossy())?;
+    assert_eq!(reloaded.xmx.as_deref(), Some("4G"));
+    let manipulator = ServerManipulator { server: reloaded };
+    assert_eq!(
+        manipulator.get_property("allow-nether").as_deref(),
+        Some("false")
+    );
+    assert_eq!(manipulator.get_property("server-port"), None);
+    Ok(())
+}
+
+#[test]
+fn deletes_templates_and_rejects_bad_names() -> Result<(), Error> {
+    let server = minigame_server("template-delete")?;
+    let store = temp_dir("template-delete-store");
+    let templates = Templates::at(&store)?;
+    templates.create_from(&server, "lobby")?;
+
+    templates.delete("lobby")?;
+
+    assert!(templates.list()?.is_empty());
+    assert!(templates.get("lobby").is_err());
+    assert!(matches!(
+        templates.create_from(&server, "../escape"),
+        Err(Error::InvalidInput(_))
+    ));
+    Ok(())
+}
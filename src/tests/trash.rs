@@ -0,0 +1,42 @@
+use crate::error::Error;
+use crate::minecraft::trash::Trash;
+use crate::tests::paper_server;
+use crate::tests::temp_dir;
+
+#[test]
+fn trashes_and_restores_a_server() -> Result<(), Error> {
+    let bin = temp_dir("trash-restore-bin");
+    let trash = Trash::at(&bin)?;
+    let server = paper_server("trash-restore");
+    std::fs::write(server.location.join("server.properties"), b"motd=hi")?;
+
+    let entry = trash.put(&server)?;
+
+    assert!(!server.location.exists());
+    assert_eq!(trash.list()?.len(), 1);
+    assert_eq!(trash.find("TRASH-RESTORE")?.id, entry.id);
+
+    trash.restore(&trash.find(&entry.id)?)?;
+
+    assert_eq!(
+        std::fs::read(server.location.join("server.properties"))?,
+        b"motd=hi"
+    );
+    assert!(trash.list()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn refuses_to_restore_over_an_existing_folder() -> Result<(), Error> {
+    let bin = temp_dir("trash-conflict-bin");
+    let trash = Trash::at(&bin)?;
+    let server = paper_server("trash-conflict");
+    let entry = trash.put(&server)?;
+    std::fs::create_dir_all(&server.location)?;
+
+    assert!(trash.restore(&entry).is_err());
+
+    trash.remove(&entry)?;
+    assert!(trash.list()?.is_empty());
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use crate::error::Error;
-use reqwest::blocking::Response;
+use directories::ProjectDirs;
+use reqwest::blocking::{Client, Response};
 use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
 use std::fs;
+use std::cmp::Ordering;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -94,3 +98,254 @@ pub fn canonize(path: &Path) -> Result<PathBuf, Error> {
     let full_path = PathBuf::from(full_path);
     Ok(full_path)
 }
+
+// Compares dotted versions (1.20.4, 1.13, 3.3.0-SNAPSHOT) component by component
+// Numeric parts of a version, ignoring a leading v and tags like -bukkit
+pub fn version_parts(version: &str) -> Vec<u32> {
+    version
+        .strip_prefix(['v', 'V'])
+        .unwrap_or(version)
+        .split(['.', '-', '+'])
+        .filter_map(|part| part.parse::<u32>().ok())
+        .collect()
+}
+
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a
+            .get(i)
+            .unwrap_or(&0)
+            .cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+pub fn http_client() -> Result<Client, Error> {
+    let client = Client::builder()
+        .user_agent(concat!("Azuyamat/boxes/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    Ok(client)
+}
+
+// Hashes are written as `algorithm:hex`, e.g. `sha512:9f86d0...`
+pub fn hash_bytes(algorithm: &str, bytes: &[u8]) -> Result<String, Error> {
+    let digest = match algorithm {
+        "sha1" => Sha1::digest(bytes).to_vec(),
+        "sha256" => Sha256::digest(bytes).to_vec(),
+        "sha512" => Sha512::digest(bytes).to_vec(),
+        _ => {
+            return Err(Error::ResourceNotFound(format!(
+                "Unsupported hash algorithm {algorithm}"
+            )))
+        }
+    };
+    Ok(format_hash(algorithm, &digest))
+}
+
+fn format_hash(algorithm: &str, digest: &[u8]) -> String {
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{algorithm}:{hex}")
+}
+
+pub fn verify_hash(bytes: &[u8], expected: &str) -> Result<(), Error> {
+    let algorithm = expected.split(':').next().unwrap_or_default();
+    let actual = hash_bytes(algorithm, bytes)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(Error::HashMismatch {
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+pub fn hash_file(algorithm: &str, path: &Path) -> Result<String, Error> {
+    hash_reader(algorithm, File::open(path)?)
+}
+
+pub fn hash_reader(algorithm: &str, mut reader: impl Read) -> Result<String, Error> {
+    fn digest<D: Digest>(reader: &mut impl Read) -> Result<Vec<u8>, Error> {
+        let mut hasher = D::new();
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(hasher.finalize().to_vec())
+    }
+    let digest = match algorithm {
+        "sha1" => digest::<Sha1>(&mut reader)?,
+        "sha256" => digest::<Sha256>(&mut reader)?,
+        "sha512" => digest::<Sha512>(&mut reader)?,
+        _ => {
+            return Err(Error::ResourceNotFound(format!(
+                "Unsupported hash algorithm {algorithm}"
+            )))
+        }
+    };
+    Ok(format_hash(algorithm, &digest))
+}
+
+// Where boxes keeps its own files (backups, trash, runtimes...)
+pub fn data_dir() -> Result<PathBuf, Error> {
+    let dirs = ProjectDirs::from("rs", "", "boxes").ok_or(Error::ResourceNotFound(
+        "Home directory not found".to_string(),
+    ))?;
+    Ok(dirs.data_dir().to_path_buf())
+}
+
+// Where confy keeps the boxes config, and the templates next to it
+pub fn config_dir() -> Result<PathBuf, Error> {
+    let dirs = ProjectDirs::from("rs", "", "boxes").ok_or(Error::ResourceNotFound(
+        "Home directory not found".to_string(),
+    ))?;
+    Ok(dirs.config_dir().to_path_buf())
+}
+
+// Every file under `dir`, relative to it, skipping the paths in `exclude`
+pub fn walk_files(dir: &Path, exclude: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            if exclude.iter().any(|e| path.starts_with(e)) {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.is_file() {
+                files.push(path.strip_prefix(dir).unwrap_or(&path).to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Moves a folder to a new path, copying it only when the rename crosses filesystems
+pub fn move_dir(from: &Path, to: &Path) -> Result<(), Error> {
+    if to.exists() {
+        return Err(Error::InvalidInput(format!(
+            "{} already exists",
+            to.display()
+        )));
+    }
+    if is_within(to, from) {
+        return Err(Error::InvalidInput(format!(
+            "Can't move {} into itself",
+            from.display()
+        )));
+    }
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() != std::io::ErrorKind::CrossesDevices => return Err(e.into()),
+        Err(_) => {}
+    }
+    // `to` didn't exist before, so whatever is there is a partial copy
+    if let Err(e) = copy_dir(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e);
+    }
+    fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+// Whether `path` is `dir` or somewhere below it, resolving links on the part that exists
+pub fn is_within(path: &Path, dir: &Path) -> bool {
+    let Ok(dir) = fs::canonicalize(dir) else {
+        return false;
+    };
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+            }
+            _ => return false,
+        }
+    }
+    let Ok(mut resolved) = fs::canonicalize(existing) else {
+        return false;
+    };
+    resolved.extend(rest.iter().rev());
+    resolved.starts_with(dir)
+}
+
+pub fn copy_dir(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CopyMethod {
+    Reflink,
+    Hardlink,
+    Copy,
+}
+
+// Copies a file as cheaply as the filesystem allows. Hard links are only used when
+// `allow_hardlink` is set, since both paths then share every later write.
+pub fn link_or_copy(from: &Path, to: &Path, allow_hardlink: bool) -> Result<CopyMethod, Error> {
+    if reflink_copy::reflink(from, to).is_ok() {
+        return Ok(CopyMethod::Reflink);
+    }
+    if allow_hardlink && fs::hard_link(from, to).is_ok() {
+        return Ok(CopyMethod::Hardlink);
+    }
+    fs::copy(from, to)?;
+    Ok(CopyMethod::Copy)
+}
+
+// Parses sizes like 512M, 6G or 1.5T into bytes
+pub fn parse_size(size: &str) -> Result<u64, Error> {
+    let size = size.trim();
+    let invalid = || Error::InvalidInput(format!("Invalid size {size} (e.g. 512M, 6G)"));
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return Err(invalid()),
+    };
+    Ok((number * multiplier as f64) as u64)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1}{}", units[unit])
+}